@@ -0,0 +1,70 @@
+//! The plain data that the inputs of the database are made of.
+
+use std::path::{Path, PathBuf};
+
+use rustc_hash::FxHashMap;
+
+/// A source file, as known to the database. The ids are handed out by
+/// whoever loads the files, and stay the same while the file is edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceRootId(pub u32);
+
+/// A set of files that are loaded together, such as the sources of a project
+/// or of a library, by their paths relative to the root.
+///
+/// The files of a library are not expected to change, so the database can
+/// skip checking the queries that only depend on them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceRoot {
+    pub is_library: bool,
+    paths: FxHashMap<FileId, PathBuf>,
+    files: FxHashMap<PathBuf, FileId>,
+}
+
+impl SourceRoot {
+    pub fn new_local() -> SourceRoot {
+        SourceRoot::default()
+    }
+
+    pub fn new_library() -> SourceRoot {
+        SourceRoot {
+            is_library: true,
+            ..SourceRoot::default()
+        }
+    }
+
+    /// Adds `file_id` at `path`, replacing the file that was there.
+    pub fn insert_file(&mut self, file_id: FileId, path: impl Into<PathBuf>) {
+        let path = path.into();
+        if let Some(old) = self.files.insert(path.clone(), file_id) {
+            self.paths.remove(&old);
+        }
+        if let Some(old) = self.paths.insert(file_id, path) {
+            self.files.remove(&old);
+        }
+    }
+
+    pub fn file_for_path(&self, path: &Path) -> Option<FileId> {
+        self.files.get(path).copied()
+    }
+
+    pub fn path_for_file(&self, file_id: FileId) -> Option<&Path> {
+        self.paths.get(&file_id).map(PathBuf::as_path)
+    }
+
+    /// The files of the root, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = FileId> + '_ {
+        self.paths.keys().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
@@ -0,0 +1,256 @@
+//! The base of the incremental compiler: the texts of files and the source
+//! roots they belong to, which are the inputs of the database, and the
+//! queries that turn them into syntax trees.
+//!
+//! Queries are memoized, and after an edit only the queries that depend on
+//! the edited inputs are run again. A database can be cloned to read it from
+//! other threads; an edit then cancels the readers, which unwind with
+//! [`Cancelled`] and are expected to retry on the new revision.
+
+mod input;
+#[cfg(test)]
+mod tests;
+
+use std::{hash::Hash, sync::RwLock};
+
+use rustc_hash::FxHashMap;
+use salsa::Setter;
+pub use salsa::{self, Cancelled, Durability};
+use syntax::{Parse, SourceFile};
+use triomphe::Arc;
+
+pub use crate::input::{FileId, SourceRoot, SourceRootId};
+
+/// The result of reading a database that may be cancelled by an edit.
+pub type Cancellable<T> = Result<T, Cancelled>;
+
+#[salsa::input]
+pub struct FileText {
+    pub file_id: FileId,
+    #[return_ref]
+    pub text: Arc<str>,
+}
+
+/// The source root of a file, `None` once the file is removed from its root.
+#[salsa::input]
+pub struct FileSourceRootInput {
+    pub source_root_id: Option<SourceRootId>,
+}
+
+#[salsa::input]
+pub struct SourceRootInput {
+    pub source_root: Arc<SourceRoot>,
+}
+
+/// The inputs of a database by their ids, shared by its clones.
+///
+/// An id gets its input when it is first set, and keeps it afterwards, so
+/// that queries can look inputs up by id without depending on the table.
+#[derive(Debug, Default, Clone)]
+pub struct Files {
+    files: Arc<RwLock<FxHashMap<FileId, FileText>>>,
+    source_roots: Arc<RwLock<FxHashMap<SourceRootId, SourceRootInput>>>,
+    file_source_roots: Arc<RwLock<FxHashMap<FileId, FileSourceRootInput>>>,
+}
+
+impl Files {
+    /// # Panics
+    ///
+    /// Panics if the text of the file has not been set.
+    pub fn file_text(&self, file_id: FileId) -> FileText {
+        lookup(&self.files, file_id, "text")
+    }
+
+    pub fn set_file_text(
+        &self,
+        db: &mut dyn SourceDatabase,
+        file_id: FileId,
+        text: &str,
+        durability: Durability,
+    ) {
+        let existing = self.files.read().unwrap().get(&file_id).copied();
+        match existing {
+            Some(file) => {
+                file.set_text(db)
+                    .with_durability(durability)
+                    .to(text.into());
+            },
+            None => {
+                let file = FileText::builder(file_id, text.into())
+                    .durability(durability)
+                    .new(db);
+                self.files.write().unwrap().insert(file_id, file);
+            },
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the source root has not been set.
+    pub fn source_root(&self, source_root_id: SourceRootId) -> SourceRootInput {
+        lookup(&self.source_roots, source_root_id, "source root")
+    }
+
+    fn existing_source_root(&self, source_root_id: SourceRootId) -> Option<SourceRootInput> {
+        self.source_roots
+            .read()
+            .unwrap()
+            .get(&source_root_id)
+            .copied()
+    }
+
+    pub fn set_source_root(
+        &self,
+        db: &mut dyn SourceDatabase,
+        source_root_id: SourceRootId,
+        source_root: Arc<SourceRoot>,
+        durability: Durability,
+    ) {
+        match self.existing_source_root(source_root_id) {
+            Some(input) => {
+                input
+                    .set_source_root(db)
+                    .with_durability(durability)
+                    .to(source_root);
+            },
+            None => {
+                let input = SourceRootInput::builder(source_root)
+                    .durability(durability)
+                    .new(db);
+                self.source_roots
+                    .write()
+                    .unwrap()
+                    .insert(source_root_id, input);
+            },
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the file has never been in a source root.
+    pub fn file_source_root(&self, file_id: FileId) -> FileSourceRootInput {
+        lookup(&self.file_source_roots, file_id, "source root")
+    }
+
+    pub fn set_file_source_root(
+        &self,
+        db: &mut dyn SourceDatabase,
+        file_id: FileId,
+        source_root_id: Option<SourceRootId>,
+        durability: Durability,
+    ) {
+        let existing = self
+            .file_source_roots
+            .read()
+            .unwrap()
+            .get(&file_id)
+            .copied();
+        match existing {
+            Some(input) => {
+                input
+                    .set_source_root_id(db)
+                    .with_durability(durability)
+                    .to(source_root_id);
+            },
+            None => {
+                let input = FileSourceRootInput::builder(source_root_id)
+                    .durability(durability)
+                    .new(db);
+                self.file_source_roots
+                    .write()
+                    .unwrap()
+                    .insert(file_id, input);
+            },
+        }
+    }
+}
+
+fn lookup<K, V>(map: &RwLock<FxHashMap<K, V>>, key: K, what: &str) -> V
+where
+    K: Eq + Hash + std::fmt::Debug,
+    V: Copy,
+{
+    match map.read().unwrap().get(&key) {
+        Some(&it) => it,
+        None => panic!("no {what} for {key:?}"),
+    }
+}
+
+/// The database of source files.
+///
+/// The setters run a new revision, so they wait for the clones of the
+/// database to be dropped, after cancelling what they are running.
+#[salsa::db]
+pub trait SourceDatabase: salsa::Database {
+    fn files(&self) -> &Files;
+
+    fn file_text(&self, file_id: FileId) -> FileText {
+        self.files().file_text(file_id)
+    }
+
+    fn set_file_text(&mut self, file_id: FileId, text: &str)
+    where
+        Self: Sized,
+    {
+        self.set_file_text_with_durability(file_id, text, Durability::LOW);
+    }
+
+    fn set_file_text_with_durability(&mut self, file_id: FileId, text: &str, durability: Durability)
+    where
+        Self: Sized,
+    {
+        let files = self.files().clone();
+        files.set_file_text(self, file_id, text, durability);
+    }
+
+    fn source_root(&self, source_root_id: SourceRootId) -> Arc<SourceRoot> {
+        self.files().source_root(source_root_id).source_root(self)
+    }
+
+    /// Sets the files of a source root. The files of libraries get a high
+    /// durability, so set their texts with
+    /// [`set_file_text_with_durability`](SourceDatabase::set_file_text_with_durability)
+    /// as well.
+    ///
+    /// Files that were in the root and are not anymore are left without a
+    /// root, unless another root took them in the meantime.
+    fn set_source_root(&mut self, source_root_id: SourceRootId, source_root: Arc<SourceRoot>)
+    where
+        Self: Sized,
+    {
+        let durability = match source_root.is_library {
+            true => Durability::HIGH,
+            false => Durability::LOW,
+        };
+        let files = self.files().clone();
+        if let Some(old) = files.existing_source_root(source_root_id) {
+            let old = old.source_root(self);
+            for file_id in old.iter() {
+                let removed = source_root.path_for_file(file_id).is_none()
+                    && self.file_source_root(file_id) == Some(source_root_id);
+                if removed {
+                    files.set_file_source_root(self, file_id, None, durability);
+                }
+            }
+        }
+        for file_id in source_root.iter() {
+            files.set_file_source_root(self, file_id, Some(source_root_id), durability);
+        }
+        files.set_source_root(self, source_root_id, source_root, durability);
+    }
+
+    /// The source root of the file, `None` if it was removed from its root.
+    fn file_source_root(&self, file_id: FileId) -> Option<SourceRootId> {
+        self.files().file_source_root(file_id).source_root_id(self)
+    }
+}
+
+/// The syntax tree of the file.
+pub fn parse(db: &dyn SourceDatabase, file_id: FileId) -> Parse<SourceFile> {
+    parse_file(db, db.file_text(file_id))
+}
+
+#[salsa::tracked]
+fn parse_file(db: &dyn SourceDatabase, file: FileText) -> Parse<SourceFile> {
+    SourceFile::parse(file.text(db))
+}
@@ -0,0 +1,198 @@
+use std::{
+    panic::AssertUnwindSafe,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use salsa::{Event, EventKind};
+use syntax::AstNode;
+
+use super::*;
+
+/// A database that records the queries that were run.
+#[salsa::db]
+#[derive(Default, Clone)]
+struct TestDb {
+    storage: salsa::Storage<Self>,
+    files: Files,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestDb {
+    fn with_files(texts: &[&str]) -> TestDb {
+        let mut db = TestDb::default();
+        for (i, text) in texts.iter().enumerate() {
+            db.set_file_text(FileId(i as u32), text);
+        }
+        db
+    }
+
+    /// The queries that were run since the last call, as `query(input)`.
+    fn take_log(&self) -> Vec<String> {
+        std::mem::take(&mut self.log.lock().unwrap())
+    }
+}
+
+#[salsa::db]
+impl salsa::Database for TestDb {
+    fn salsa_event(&self, event: &dyn Fn() -> Event) {
+        if let EventKind::WillExecute { database_key } = event().kind {
+            let query = self.ingredient_debug_name(database_key.ingredient_index());
+            let input = database_key.key_index().as_u32();
+            self.log.lock().unwrap().push(format!("{query}({input})"));
+        }
+    }
+}
+
+#[salsa::db]
+impl SourceDatabase for TestDb {
+    fn files(&self) -> &Files {
+        &self.files
+    }
+}
+
+#[salsa::tracked]
+fn item_count(db: &dyn SourceDatabase, file: FileText) -> usize {
+    let parse = parse(db, file.file_id(db));
+    parse.tree().syntax().children().count()
+}
+
+#[test]
+fn edit_reruns_queries_of_the_file() {
+    let mut db = TestDb::with_files(&["let x = 1", "let y = 2\nlet z = 3"]);
+    let count = |db: &TestDb, file_id| item_count(db, db.file_text(file_id));
+    assert_eq!(count(&db, FileId(0)), 1);
+    assert_eq!(count(&db, FileId(1)), 2);
+    assert_eq!(db.take_log(), [
+        "item_count(0)",
+        "parse_file(0)",
+        "item_count(1)",
+        "parse_file(1)"
+    ]);
+
+    db.set_file_text(FileId(1), "let y = 2");
+    assert_eq!(count(&db, FileId(0)), 1);
+    assert_eq!(count(&db, FileId(1)), 1);
+    assert_eq!(db.take_log(), ["parse_file(1)", "item_count(1)"]);
+    assert_eq!(
+        parse(&db, FileId(1)).tree().syntax().to_string(),
+        "let y = 2"
+    );
+    assert_eq!(db.take_log(), Vec::<String>::new());
+}
+
+#[test]
+fn same_tree_is_not_recomputed() {
+    let mut db = TestDb::with_files(&["let x = 1"]);
+    item_count(&db, db.file_text(FileId(0)));
+    db.take_log();
+
+    // The file is parsed again, but to the same tree, so what depends on the
+    // tree is kept.
+    db.set_file_text(FileId(0), "let x = 1");
+    assert_eq!(item_count(&db, db.file_text(FileId(0))), 1);
+    assert_eq!(db.take_log(), ["parse_file(0)"]);
+}
+
+#[test]
+fn source_roots() {
+    let mut db = TestDb::with_files(&["let x = 1", "let y = 2"]);
+    let mut local = SourceRoot::new_local();
+    local.insert_file(FileId(0), "main.fsic");
+    let mut library = SourceRoot::new_library();
+    library.insert_file(FileId(1), "std/list.fsic");
+    db.set_source_root(SourceRootId(0), Arc::new(local));
+    db.set_source_root(SourceRootId(1), Arc::new(library));
+
+    assert_eq!(db.file_source_root(FileId(0)), Some(SourceRootId(0)));
+    assert_eq!(db.file_source_root(FileId(1)), Some(SourceRootId(1)));
+    let library = db.source_root(SourceRootId(1));
+    assert!(library.is_library);
+    assert_eq!(
+        library.file_for_path("std/list.fsic".as_ref()),
+        Some(FileId(1))
+    );
+    assert_eq!(
+        library.path_for_file(FileId(1)),
+        Some("std/list.fsic".as_ref())
+    );
+
+    // A file moves to another root.
+    let mut local = SourceRoot::new_local();
+    local.insert_file(FileId(0), "main.fsic");
+    local.insert_file(FileId(1), "list.fsic");
+    db.set_source_root(SourceRootId(0), Arc::new(local));
+    assert_eq!(db.file_source_root(FileId(1)), Some(SourceRootId(0)));
+}
+
+#[salsa::tracked]
+fn root_of(db: &dyn SourceDatabase, file: FileText) -> Option<SourceRootId> {
+    db.file_source_root(file.file_id(db))
+}
+
+#[test]
+fn files_removed_from_a_root() {
+    let mut db = TestDb::with_files(&["let x = 1", "let y = 2", "let z = 3"]);
+    let root = |files: &[(u32, &str)]| {
+        let mut root = SourceRoot::new_local();
+        for &(file_id, path) in files {
+            root.insert_file(FileId(file_id), path);
+        }
+        Arc::new(root)
+    };
+    let root_of = |db: &TestDb, file_id| root_of(db, db.file_text(FileId(file_id)));
+    db.set_source_root(SourceRootId(0), root(&[(0, "a.fsic"), (1, "b.fsic")]));
+    db.set_source_root(SourceRootId(1), root(&[(2, "c.fsic")]));
+    assert_eq!(root_of(&db, 1), Some(SourceRootId(0)));
+
+    // The new root takes the file before the old one lets it go.
+    db.set_source_root(SourceRootId(1), root(&[(1, "b.fsic"), (2, "c.fsic")]));
+    db.set_source_root(SourceRootId(0), root(&[(0, "a.fsic")]));
+    assert_eq!(root_of(&db, 1), Some(SourceRootId(1)));
+
+    // The old root lets the file go before the new one takes it.
+    db.set_source_root(SourceRootId(1), root(&[(2, "c.fsic")]));
+    assert_eq!(root_of(&db, 1), None);
+    db.set_source_root(SourceRootId(0), root(&[(0, "a.fsic"), (1, "b.fsic")]));
+    assert_eq!(root_of(&db, 1), Some(SourceRootId(0)));
+    assert_eq!(root_of(&db, 0), Some(SourceRootId(0)));
+    assert_eq!(root_of(&db, 2), Some(SourceRootId(1)));
+}
+
+/// Set once [`wait_for_cancellation`] runs.
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Runs until the revision is cancelled.
+#[salsa::tracked]
+fn wait_for_cancellation(db: &dyn SourceDatabase, _file: FileText) -> usize {
+    STARTED.store(true, Ordering::Release);
+    loop {
+        db.unwind_if_revision_cancelled();
+        thread::yield_now();
+    }
+}
+
+#[test]
+fn edit_cancels_readers() {
+    let mut db = TestDb::with_files(&["let x = 1"]);
+    let reader = thread::spawn({
+        let db = db.clone();
+        move || {
+            Cancelled::catch(AssertUnwindSafe(|| {
+                wait_for_cancellation(&db, db.file_text(FileId(0)))
+            }))
+        }
+    });
+    while !STARTED.load(Ordering::Acquire) {
+        thread::yield_now();
+    }
+
+    // Waits for the reader to unwind and drop its database.
+    db.set_file_text(FileId(0), "let x = 2");
+    let res: Cancellable<usize> = reader.join().unwrap();
+    assert!(matches!(res, Err(Cancelled::PendingWrite { .. })));
+    assert_eq!(item_count(&db, db.file_text(FileId(0))), 1);
+}
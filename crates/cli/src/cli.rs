@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Parse a file and print its syntax tree and errors.
+    Parse(ParseArgs),
+    /// Print a file with its syntax highlighted.
+    Highlight(HighlightArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ParseArgs {
+    /// The file to parse. When not specified, reads from stdin.
+    pub file: Option<PathBuf>,
+    /// Leave out whitespace, newlines and comments.
+    #[arg(short, long)]
+    pub compact: bool,
+    /// Print the tree and the errors as JSON.
+    #[arg(long, conflicts_with = "compact")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct HighlightArgs {
+    /// The file to highlight. When not specified, reads from stdin.
+    pub file: Option<PathBuf>,
+    /// Print HTML instead of the escape codes of terminals.
+    #[arg(long)]
+    pub html: bool,
+}
@@ -0,0 +1,62 @@
+mod cli;
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+use anyhow::Context;
+use clap::Parser;
+use syntax::{SourceFile, highlight};
+
+use self::cli::{Cli, Command, HighlightArgs, ParseArgs};
+
+fn main() -> anyhow::Result<()> {
+    let config = Cli::parse();
+
+    match config.command {
+        Command::Parse(args) => parse(args),
+        Command::Highlight(args) => highlight(args),
+    }
+}
+
+fn parse(args: ParseArgs) -> anyhow::Result<()> {
+    let text = read_input(args.file.as_deref())?;
+    let parse = SourceFile::parse(&text);
+    if args.json {
+        println!("{}", serde_json::to_string(&parse)?);
+        return Ok(());
+    }
+    let dump = match args.compact {
+        true => parse.debug_dump_compact(),
+        false => parse.debug_dump(),
+    };
+    print!("{dump}");
+    Ok(())
+}
+
+fn highlight(args: HighlightArgs) -> anyhow::Result<()> {
+    let text = read_input(args.file.as_deref())?;
+    let node = SourceFile::parse(&text).syntax_node();
+    let highlighted = match args.html {
+        true => highlight::highlight_as_html(&node),
+        false => highlight::highlight_as_ansi(&node),
+    };
+    print!("{highlighted}");
+    Ok(())
+}
+
+/// Reads `file`, or stdin when there is no file.
+fn read_input(file: Option<&Path>) -> anyhow::Result<String> {
+    match file {
+        Some(path) => {
+            fs::read_to_string(path).with_context(|| format!("can't read {}", path.display()))
+        },
+        None => {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)?;
+            Ok(buf)
+        },
+    }
+}
@@ -13,20 +13,97 @@ impl Token {
 
 #[derive(Logos, Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TokenKind {
+    #[token("alias")]
+    Alias,
+    #[token("and")]
+    And,
+    #[token("as")]
+    As,
+    #[token("attribute")]
+    Attribute,
+    #[token("class")]
+    Class,
+    #[token("else")]
+    Else,
+    #[token("false")]
+    False,
+    #[token("if")]
+    If,
+    #[token("import")]
+    Import,
+    #[token("in")]
+    In,
     #[token("let")]
     Let,
+    #[token("match")]
+    Match,
     #[token("module")]
     Module,
+    #[token("rec")]
+    Rec,
+    #[token("then")]
+    Then,
+    #[token("true")]
+    True,
+    #[token("union")]
+    Union,
+    #[token("value")]
+    Value,
+    #[token("when")]
+    When,
+    #[token("where")]
+    Where,
+    #[token("with")]
+    With,
 
+    // Punctuation is lexed one character at a time, the parser glues `->`,
+    // `::`, `<>`, `<=`, `>=` and `||` from joint characters. `&` is not an
+    // operator on its own, so `&&` is lexed as a whole.
     #[token("=")]
     Eq,
+    #[token("<")]
+    Lt,
+    #[token(">")]
+    Gt,
+    #[token("&&")]
+    And2,
+    #[token("+")]
+    Plus,
+    #[token("-")]
+    Minus,
+    #[token("/")]
+    Slash,
+    #[token("%")]
+    Percent,
+    #[token(",")]
+    Comma,
+    #[token(";")]
+    Semicolon,
+    #[token(".")]
+    Dot,
+    #[token(":")]
+    Colon,
+    #[token("*")]
+    Star,
+    #[token("_", priority = 3)]
+    Underscore,
+    #[token("|")]
+    Pipe,
 
     #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
     Ident,
 
-    #[regex("[ ]")]
+    /// `[<Name>]`, lexed as a whole so that it never competes with `[`.
+    #[regex(r"\[<[^>\n]*>\]")]
+    AttributeUse,
+
+    #[regex("[ ]+")]
     Whitespace,
 
+    #[regex("//[^\n]*")]
+    #[regex(r"/\*[^*]*\*+([^/*][^*]*\*+)*/")]
+    Comment,
+
     #[regex("(\n|\r\n)")]
     Newline,
 
@@ -36,9 +113,33 @@ pub enum TokenKind {
     #[token(")")]
     CloseParen,
 
-    #[regex("[0-9]+")]
+    #[token("[")]
+    OpenBracket,
+
+    #[token("]")]
+    CloseBracket,
+
+    #[token("{")]
+    OpenCurly,
+
+    #[token("}")]
+    CloseCurly,
+
+    #[regex("[0-9]+([a-zA-Z][a-zA-Z0-9_]*)?")]
     Number,
 
+    #[regex(r"[0-9]+\.[0-9]+")]
+    Float,
+
+    #[regex(r#""([^"\\\n]|\\.)*""#)]
+    String,
+
+    #[regex(r#"f"([^"\\\n]|\\.)*""#)]
+    FString,
+
+    #[regex(r"'([^'\\\n]|\\.)'")]
+    Char,
+
     Unknown,
 
     Eof,
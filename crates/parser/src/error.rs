@@ -0,0 +1,156 @@
+//! See [`ParseError`].
+
+use std::fmt;
+
+use crate::SyntaxKind::{self, *};
+
+/// What went wrong, independent of the wording of the message. Tools match on
+/// this instead of on the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// A token that doesn't fit, as in "expected `=`, found `then`".
+    UnexpectedToken,
+    /// A `[` list whose contents contradict its position.
+    MismatchedBrackets,
+    /// Constraints anywhere but right after `let`.
+    MisplacedConstraints,
+    /// A `module` without items.
+    EmptyModule,
+    /// Input left over after a complete construct.
+    TrailingInput,
+    /// A token the lexer couldn't make sense of.
+    InvalidToken,
+    /// A token that breaks the offside rule.
+    Offside,
+    /// `let rec` on something that isn't a function.
+    RecNonFunction,
+    /// The same attribute applied twice to an item.
+    DuplicateAttr,
+    /// An integer literal that doesn't fit into 64 bits.
+    IntOverflow,
+    /// A `union` without cases.
+    EmptyUnion,
+    /// An operator definition for something that isn't an operator.
+    UnknownOperator,
+    /// A `class` without type variables.
+    NoTypeVars,
+    /// A `_` type in the signature of an item.
+    InferTypeInSignature,
+    /// A literal with a bad escape, an f-string with a bad hole, and the like.
+    InvalidLiteral,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::UnexpectedToken => "E0001",
+            ErrorCode::MismatchedBrackets => "E0002",
+            ErrorCode::MisplacedConstraints => "E0003",
+            ErrorCode::EmptyModule => "E0004",
+            ErrorCode::TrailingInput => "E0005",
+            ErrorCode::InvalidToken => "E0006",
+            ErrorCode::Offside => "E0007",
+            ErrorCode::RecNonFunction => "E0008",
+            ErrorCode::DuplicateAttr => "E0009",
+            ErrorCode::IntOverflow => "E0010",
+            ErrorCode::EmptyUnion => "E0011",
+            ErrorCode::UnknownOperator => "E0012",
+            ErrorCode::NoTypeVars => "E0013",
+            ErrorCode::InferTypeInSignature => "E0014",
+            ErrorCode::InvalidLiteral => "E0015",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A range of input tokens, counted the same way as `n_input_tokens`.
+///
+/// An empty range points between two tokens, right after the end of the first
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl TokenRange {
+    pub fn new(start: u32, end: u32) -> TokenRange {
+        assert!(start <= end);
+        TokenRange { start, end }
+    }
+
+    pub fn empty(at: u32) -> TokenRange {
+        TokenRange::new(at, at)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// A secondary range of a [`ParseError`], explaining how the parser got there.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorLabel {
+    pub range: TokenRange,
+    pub message: String,
+}
+
+/// An error produced by the parser, in the spirit of rustc's diagnostics: a
+/// message at a primary range, with optional secondary labels and notes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub range: TokenRange,
+    pub labels: Vec<ErrorLabel>,
+    pub notes: Vec<String>,
+}
+
+impl ParseError {
+    pub fn new(code: ErrorCode, message: impl Into<String>, range: TokenRange) -> ParseError {
+        ParseError {
+            code,
+            message: message.into(),
+            range,
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_label(mut self, range: TokenRange, message: impl Into<String>) -> ParseError {
+        let message = message.into();
+        self.labels.push(ErrorLabel { range, message });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> ParseError {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// How a token of `kind` is called in error messages, as in "found `then`".
+pub(crate) fn describe(kind: SyntaxKind) -> String {
+    if let Some(text) = kind.text() {
+        return format!("`{text}`");
+    }
+    let description = match kind {
+        EOF => "end of input",
+        IDENT => "an identifier",
+        ATTRIBUTE => "an attribute",
+        _ if kind.is_literal() => "a literal",
+        _ => return format!("{kind:?}"),
+    };
+    description.to_owned()
+}
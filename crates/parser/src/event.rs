@@ -1,4 +1,5 @@
 use crate::SyntaxKind::{self, *};
+use crate::error::ParseError;
 use crate::output::Output;
 
 /// `Parser` produces a flat list of `Event`s.
@@ -62,7 +63,7 @@ pub(crate) enum Event {
         n_raw_tokens: u8,
     },
     Error {
-        msg: String,
+        err: Box<ParseError>,
     },
 }
 
@@ -76,7 +77,7 @@ impl Event {
 }
 
 pub(super) fn process(mut events: Vec<Event>) -> Output {
-    let mut res = Output::default();
+    let mut res = Output::with_capacity(events.len());
     let mut forward_parents = Vec::new();
 
     for i in 0..events.len() {
@@ -119,7 +120,7 @@ pub(super) fn process(mut events: Vec<Event>) -> Output {
             Event::Token { kind, n_raw_tokens } => {
                 res.token(kind, n_raw_tokens);
             },
-            Event::Error { msg } => res.error(msg),
+            Event::Error { err } => res.error(*err),
         }
     }
 
@@ -0,0 +1,145 @@
+//! `[` opens both a `TypeArgList` (`Option[int]`) and a `ConstraintList`
+//! (`let [Eq a] f x`, `class [Eq a] Ord a`, `union Tree [Ord a] = ...`).
+//!
+//! The choice is deterministic and made in two steps:
+//!
+//! 1. The path that starts the contents is skipped, and the token after it is
+//!    inspected. An identifier (`[Eq a]`, `[Std.Eq a]`) can only follow the
+//!    class of a constraint, while an arrow, a star or a nested bracket can
+//!    only follow a type (`[int -> a]`, `[Std.Option[a]]`, `[a * b]`). A
+//!    parenthesis or an underscore right after `[` can only start a type (`[(a
+//!    * b)]`, `[_]`).
+//! 2. When that can't tell (`[a]`, `[a, b]`, `[]`), the position decides: right
+//!    after a type name it's a type argument list, everywhere else it's a
+//!    constraint list.
+//!
+//! When the lookahead contradicts the position, the contents win, so that the
+//! tree matches what was written, and an error names the interpretation that
+//! was chosen.
+
+use super::*;
+use crate::grammar::{paths, types};
+
+/// What the position allows, i.e. how an undecidable `[` is read.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) enum Brackets {
+    TypeArgs,
+    Constraints,
+}
+
+/// What the contents of a `[` look like, judging by the token after the
+/// leading path.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Guess {
+    TypeArgs,
+    Constraints,
+    Unknown,
+}
+
+fn guess(p: &Parser<'_>) -> Guess {
+    assert!(p.at(T!['[']));
+    if !p.nth_at(1, IDENT) {
+        return match p.nth(1).kind {
+            T!['('] | T!['['] | T![_] => Guess::TypeArgs,
+            _ => Guess::Unknown,
+        };
+    }
+    let mut n = 1;
+    while p.nth_at(n + 1, T![.]) && p.nth_at(n + 2, IDENT) {
+        n += 2;
+    }
+    if p.nth_at(n + 1, IDENT) {
+        Guess::Constraints
+    } else if p.nth_at(n + 1, T![->]) || p.nth_at(n + 1, T![*]) || p.nth_at(n + 1, T!['[']) {
+        Guess::TypeArgs
+    } else {
+        Guess::Unknown
+    }
+}
+
+/// Parses a bracketed list at a position that expects `expected`.
+// test constraint_list
+// let [Eq a, Std.Ord b] f : [Show c] c -> Option[c] = g
+// class [Eq a] Ord a =
+//     let compare
+// union Tree [a] = Node[a]
+// alias A = Option[Std.Int -> a]
+// test_err brackets_contradicting_position
+// alias A = Option[Eq a]
+// alias B = Option[Std.Eq a]
+// class [Std.Option[a]] Eq a =
+// let f [Eq a] x
+pub(super) fn brackets(p: &mut Parser<'_>, expected: Brackets) {
+    let start = p.current_range().start;
+    let (message, note) = match (expected, guess(p)) {
+        (Brackets::TypeArgs, Guess::Constraints) => {
+            constraint_list(p);
+            (
+                "expected type arguments, found constraints",
+                "`[` was parsed as a constraint list",
+            )
+        },
+        (Brackets::Constraints, Guess::TypeArgs) => {
+            type_arg_list(p);
+            (
+                "expected constraints, found type arguments",
+                "`[` was parsed as a type argument list",
+            )
+        },
+        (Brackets::TypeArgs, _) => return type_arg_list(p),
+        (Brackets::Constraints, _) => return constraint_list(p),
+    };
+    let range = TokenRange::new(start, p.current_range().start);
+    p.push_error(ParseError::new(ErrorCode::MismatchedBrackets, message, range).with_note(note));
+}
+
+/// Parses an optional constraint list in an item head.
+pub(super) fn opt_constraint_list(p: &mut Parser<'_>) {
+    if p.at(T!['[']) {
+        brackets(p, Brackets::Constraints);
+    }
+}
+
+fn constraint_list(p: &mut Parser<'_>) {
+    let m = p.start();
+    p.bump(T!['[']);
+    while !p.at(EOF) && !p.at(T![']']) {
+        if !p.at(IDENT) {
+            p.error("expected a constraint");
+            break;
+        }
+        constraint(p);
+        if !p.at(T![']']) && !p.expect(T![,]) {
+            break;
+        }
+    }
+    p.expect(T![']']);
+    m.complete(p, CONSTRAINT_LIST);
+}
+
+fn constraint(p: &mut Parser<'_>) {
+    let m = p.start();
+    // `Eq a` and `Std.Eq a` name a class, a lone `a` is just a type variable.
+    if matches!(p.nth(1).kind, IDENT | T![.]) {
+        paths::plain_path(p);
+    }
+    type_vars(p);
+    m.complete(p, CONSTRAINT);
+}
+
+fn type_arg_list(p: &mut Parser<'_>) {
+    let m = p.start();
+    p.bump(T!['[']);
+    while !p.at(EOF) && !p.at(T![']']) {
+        if !types::is_type_start(p) {
+            p.error("expected a type");
+            break;
+        }
+        types::type_(p);
+        if !p.at(T![']']) && !p.expect(T![,]) {
+            break;
+        }
+    }
+    p.expect(T![']']);
+    m.complete(p, TYPE_ARG_LIST);
+}
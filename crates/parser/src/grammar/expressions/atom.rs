@@ -0,0 +1,178 @@
+use super::*;
+use crate::grammar::{paths, patterns};
+
+pub(crate) fn is_literal_start(p: &Parser<'_>) -> bool {
+    p.current().kind.is_literal() || p.nth_at(0, T![true]) || p.nth_at(0, T![false])
+}
+
+// test literal
+// let xs = 92, 9.2, "92", f"{n}", '9', true, false
+// test literal_suffix
+// let x = 92u8 + 1i64
+pub(crate) fn literal(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    if !is_literal_start(p) {
+        return None;
+    }
+    let m = p.start();
+    p.bump_any();
+    Some(m.complete(p, LITERAL))
+}
+
+/// Whether the current token can be an argument of an application, as `x`
+/// in `f x`.
+pub(super) fn is_arg_start(p: &Parser<'_>) -> bool {
+    is_literal_start(p) || p.nth_at(0, IDENT) || p.nth_at(0, T!['('])
+}
+
+pub(super) fn is_atom_start(p: &Parser<'_>) -> bool {
+    is_arg_start(p) || p.nth_at(0, T![if]) || p.nth_at(0, T![match])
+}
+
+/// Tokens that close or continue an enclosing construct, so that a missing
+/// expression doesn't swallow them.
+const EXPR_RECOVERY_SET: TokenSet = TokenSet::new(&[
+    T![')'],
+    T![']'],
+    T!['}'],
+    T![,],
+    T![;],
+    T![|],
+    T![->],
+    T![then],
+    T![else],
+    T![with],
+    T![in],
+    T![let],
+]);
+
+// test_err atom_expr_recovery
+// let x = f (1 + ) y
+// let y = ]
+pub(super) fn atom_expr(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    if let Some(m) = literal(p) {
+        return Some(m);
+    }
+
+    let done = match p.current().kind {
+        IDENT => path_expr(p),
+        T!['('] => paren_expr(p),
+        T![if] => if_expr(p),
+        T![match] => match_expr(p),
+        _ if p.at_block_boundary() => {
+            p.error("expected an expression");
+            return None;
+        },
+        _ => {
+            p.err_recover("expected an expression", EXPR_RECOVERY_SET);
+            return None;
+        },
+    };
+    Some(done)
+}
+
+// test path_expr
+// let f = Std.List.map
+fn path_expr(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    paths::plain_path(p);
+    m.complete(p, PATH_EXPR)
+}
+
+// test paren_expr
+// let x = ()
+// let y = (a, (b))
+// test_err paren_expr_unclosed
+// let x = (f 1
+// let y = (a, b
+// let z = (a
+// , b)
+fn paren_expr(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    p.bump(T!['(']);
+    if !p.at(T![')']) {
+        block_expr(p);
+    }
+    p.expect(T![')']);
+    m.complete(p, PAREN_EXPR)
+}
+
+// test if_expr
+// let x = if c then a else b
+// let y =
+//     if c
+//     then a
+//     else b
+// let z = if c then a
+// test_err if_expr_offside_else
+// let f x =
+//     if a then b
+//   else c
+fn if_expr(p: &mut Parser<'_>) -> CompletedMarker {
+    let ctx = p.new_keyword_context();
+    let m = p.start();
+    p.bump(T![if]);
+    expr_no_seq(p);
+    // Nothing but this `if` can take a `then`, so it never stops the `if`.
+    if p.nth_at(0, T![then]) && p.current().col < ctx.col {
+        p.push_error(p.offside_error(ctx));
+    }
+    if p.expect(T![then]) {
+        body(p);
+    }
+    if p.at_aligned(T![else]) {
+        p.bump(T![else]);
+        body(p);
+    }
+    let done = m.complete(p, IF_EXPR);
+    p.drop_indent_block();
+    done
+}
+
+// test match_expr
+// let f x =
+//     match x with
+//     | Some(v) when v > 0 -> v
+//     | _ -> 0
+// let g x = match x with A -> 1 | B -> 2
+// test_err match_expr_offside_case
+// let f x =
+//     match x with
+//     | A -> 1
+//   | B -> 2
+fn match_expr(p: &mut Parser<'_>) -> CompletedMarker {
+    let ctx = p.new_keyword_context();
+    let m = p.start();
+    p.bump(T![match]);
+    expr_no_seq(p);
+    if p.nth_at(0, T![with]) && p.current().col < ctx.col {
+        p.push_error(p.offside_error(ctx));
+    }
+    if p.expect(T![with]) {
+        if p.at_aligned(T![|]) {
+            p.bump(T![|]);
+        }
+        match_case(p);
+        while p.at_aligned(T![|]) {
+            p.bump(T![|]);
+            match_case(p);
+        }
+    }
+    let done = m.complete(p, MATCH_EXPR);
+    p.drop_indent_block();
+    done
+}
+
+fn match_case(p: &mut Parser<'_>) {
+    let m = p.start();
+    patterns::pattern(p);
+    if p.at(T![when]) {
+        let guard = p.start();
+        p.bump(T![when]);
+        expr_no_seq(p);
+        guard.complete(p, MATCH_GUARD);
+    }
+    if p.expect(T![->]) {
+        body(p);
+    }
+    m.complete(p, MATCH_CASE);
+}
@@ -0,0 +1,222 @@
+mod atom;
+
+pub(super) use self::atom::{is_literal_start, literal};
+use super::*;
+use crate::grammar::{items, types};
+
+pub(super) fn is_expr_start(p: &Parser<'_>) -> bool {
+    atom::is_atom_start(p) || p.nth_at(0, T![-]) || p.nth_at(0, T![let])
+}
+
+pub(super) fn expr(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    block_expr(p)
+}
+
+/// An expression that opens an indentation block at its first token. Lines
+/// aligned with that token are parsed as a `SEQ_EXPR`, or as the body of a
+/// preceding `let`.
+pub(super) fn block_expr(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    p.new_indent_block();
+    let res = seq_expr(p);
+    p.drop_indent_block();
+    res
+}
+
+/// The body of a `let`, an `if` branch or a match case, which may start on
+/// the next line but must be indented past the enclosing block.
+pub(super) fn body(p: &mut Parser<'_>) {
+    if p.at_block_boundary() {
+        p.error("expected an expression");
+        return;
+    }
+    block_expr(p);
+}
+
+// test seq_expr
+// let main =
+//     print "a"
+//     print "b"
+fn seq_expr(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    let lhs = if p.at(T![let]) {
+        let_expr(p)
+    } else {
+        expr_no_seq(p)?
+    };
+    if !at_next_in_block(p) {
+        return Some(lhs);
+    }
+
+    let m = lhs.precede(p);
+    seq_expr(p);
+    Some(m.complete(p, SEQ_EXPR))
+}
+
+/// Whether the current token starts the next expression of the innermost
+/// block.
+fn at_next_in_block(p: &Parser<'_>) -> bool {
+    let (block, _) = p.get_current_indent_block();
+    !p.at(EOF) && p.current().col == block && is_expr_start(p)
+}
+
+// test let_expr
+// let f =
+//     let x = 92 in x
+// let g =
+//     let x = 92
+//     x
+// test_err let_expr_without_body
+// let f = let x = 1
+fn let_expr(p: &mut Parser<'_>) -> CompletedMarker {
+    let let_range = p.current_range();
+    let m = p.start();
+    let decl = p.start();
+    items::let_decl(p, decl);
+
+    let has_in = p.eat(T![in]);
+    if (has_in && !p.at_block_boundary() && is_expr_start(p)) || at_next_in_block(p) {
+        seq_expr(p);
+    } else {
+        let err = p
+            .unexpected("expected an expression")
+            .with_label(let_range, "this `let` needs a body");
+        p.push_error(err);
+    }
+    m.complete(p, LET_EXPR)
+}
+
+fn expr_no_seq(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    let first = asc_expr(p)?;
+    if !p.at(T![,]) || p.at_block_boundary() {
+        return Some(first);
+    }
+
+    let m = first.precede(p);
+    while p.at(T![,]) && !p.at_block_boundary() {
+        p.bump(T![,]);
+        if asc_expr(p).is_none() {
+            break;
+        }
+    }
+    Some(m.complete(p, TUPLE_EXPR))
+}
+
+// test asc_expr
+// let x = (f 1 : int)
+fn asc_expr(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    let lhs = expr_bp(p, 1)?;
+    if !p.at(T![:]) || p.at_block_boundary() {
+        return Some(lhs);
+    }
+
+    let m = lhs.precede(p);
+    p.bump(T![:]);
+    types::type_(p);
+    Some(m.complete(p, ASC_EXPR))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Associativity {
+    Left,
+    Right,
+}
+
+/// Binding power of the infix operator at the current position, `0` if
+/// there is none.
+fn current_op(p: &Parser<'_>) -> (u8, Associativity) {
+    use Associativity::*;
+    match nth_operator(p, 0).unwrap_or(EOF) {
+        T![||] => (1, Left),
+        T![&&] => (2, Left),
+        T![=] | T![<>] | T![<] | T![>] | T![<=] | T![>=] => (3, Left),
+        T![::] => (4, Right),
+        T![+] | T![-] => (5, Left),
+        T![*] | T![/] | T![%] => (6, Left),
+        _ => (0, Left),
+    }
+}
+
+// test bin_expr
+// let x = a || b && c = 1 + 2 * 3
+// let xs = x :: y :: Nil
+fn expr_bp(p: &mut Parser<'_>, min_bp: u8) -> Option<CompletedMarker> {
+    let mut lhs = unary_expr(p)?;
+    loop {
+        let (bp, assoc) = current_op(p);
+        if bp == 0 || bp < min_bp || p.at_block_boundary() {
+            break;
+        }
+        let m = lhs.precede(p);
+        operator(p);
+        let rhs_bp = match assoc {
+            Associativity::Left => bp + 1,
+            Associativity::Right => bp,
+        };
+        expr_bp(p, rhs_bp);
+        lhs = m.complete(p, BIN_EXPR);
+    }
+    Some(lhs)
+}
+
+// test unary_expr
+// let x = - -a - 1
+fn unary_expr(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    if !p.at(T![-]) {
+        return app_expr(p);
+    }
+
+    let m = p.start();
+    operator(p);
+    unary_expr(p);
+    Some(m.complete(p, UNARY_EXPR))
+}
+
+// test app_expr
+// let x = f x (g y) Std.z
+fn app_expr(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    let mut lhs = atom::atom_expr(p)?;
+    // `if` and `match` extend as far as possible, nothing can be applied to them.
+    if matches!(lhs.kind(), IF_EXPR | MATCH_EXPR) {
+        return Some(lhs);
+    }
+
+    while atom::is_arg_start(p) && !p.at_block_boundary() {
+        let m = lhs.precede(p);
+        atom::atom_expr(p);
+        lhs = m.complete(p, APP_EXPR);
+    }
+    Some(lhs)
+}
+
+/// Infix operators, the composite ones first so that they are glued.
+const OPERATORS: [SyntaxKind; 14] = [
+    T![::],
+    T![<>],
+    T![<=],
+    T![>=],
+    T![||],
+    T![*],
+    T![/],
+    T![%],
+    T![+],
+    T![-],
+    T![=],
+    T![<],
+    T![>],
+    T![&&],
+];
+
+/// The operator `n` input tokens ahead. `-` of `->` is not an operator, as
+/// `->` ends a match guard.
+pub(super) fn nth_operator(p: &Parser<'_>, n: usize) -> Option<SyntaxKind> {
+    if p.nth_at(n, T![->]) {
+        return None;
+    }
+    OPERATORS.into_iter().find(|&it| p.nth_at(n, it))
+}
+
+pub(super) fn operator(p: &mut Parser<'_>) {
+    let op = nth_operator(p, 0).expect("not at an operator");
+    let m = p.start();
+    p.bump(op);
+    m.complete(p, OPERATOR);
+}
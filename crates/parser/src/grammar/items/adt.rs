@@ -0,0 +1,97 @@
+use super::*;
+use crate::parser::CompletedMarker;
+
+/// `union`, `value` and `alias` declarations, chained with `and` so that they
+/// can refer to each other.
+// test adt_list
+// union Tree [a] =
+//     | Leaf
+//     | Node[a]
+// and value Node [a] (left: Tree[a] * item: a * Tree[a])
+// alias Forest [a] = List[Tree[a]]
+pub(super) fn adt_list(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    let first = adt(p, m);
+    let list = first.precede(p);
+    while p.eat(T![and]) {
+        let m = p.start();
+        attrs(p);
+        if !matches!(p.current().kind, T![union] | T![value] | T![alias]) {
+            m.abandon(p);
+            p.error("expected `union`, `value` or `alias` after `and`");
+            break;
+        }
+        adt(p, m);
+    }
+    list.complete(p, ADT_LIST)
+}
+
+fn adt(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    match p.current().kind {
+        T![union] => union(p, m),
+        T![value] => value(p, m),
+        T![alias] => alias(p, m),
+        _ => unreachable!(),
+    }
+}
+
+// test union_without_cases
+// union Never =
+fn union(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    p.bump(T![union]);
+    name(p);
+    constraints::opt_constraint_list(p);
+    if p.expect(T![=]) {
+        p.eat(T![|]);
+        // A union without cases is reported by the validation.
+        if p.at(EOF) || p.at(T![and]) || p.at_block_boundary() {
+            return m.complete(p, UNION);
+        }
+        union_case(p);
+        while p.eat(T![|]) {
+            union_case(p);
+        }
+    }
+    m.complete(p, UNION)
+}
+
+fn union_case(p: &mut Parser<'_>) {
+    if paths::is_path_start(p) {
+        paths::path(p);
+    } else {
+        p.err_recover("expected a union case", TokenSet::new(&[T![|], T![and]]));
+    }
+}
+
+fn value(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    p.bump(T![value]);
+    name(p);
+    constraints::opt_constraint_list(p);
+    if p.eat(T!['(']) {
+        value_field(p);
+        while p.eat(T![*]) {
+            value_field(p);
+        }
+        p.expect(T![')']);
+    }
+    m.complete(p, VALUE)
+}
+
+fn value_field(p: &mut Parser<'_>) {
+    let m = p.start();
+    if p.nth_at(0, IDENT) && p.nth(1).kind == T![:] {
+        name(p);
+        p.bump(T![:]);
+    }
+    types::type_no_tuple(p);
+    m.complete(p, VALUE_FIELD);
+}
+
+fn alias(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    p.bump(T![alias]);
+    name(p);
+    constraints::opt_constraint_list(p);
+    if p.expect(T![=]) {
+        types::type_(p);
+    }
+    m.complete(p, ALIAS)
+}
@@ -0,0 +1,70 @@
+use super::*;
+use crate::{grammar::type_var, parser::CompletedMarker};
+
+// test class
+// class [Eq a] Ord a =
+//     let compare : a -> a -> int
+//     let (<) x y = compare x y < 0
+// test class_without_type_vars
+// class Marker =
+//     let marked : bool
+pub(super) fn class(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    let block = p.new_indent_block();
+    p.bump(T![class]);
+    constraints::opt_constraint_list(p);
+    name(p);
+    // A class without type variables is reported by the validation.
+    while p.at(IDENT) {
+        type_var(p);
+    }
+    if p.expect(T![=]) {
+        functions(p, block);
+    }
+    let done = m.complete(p, CLASS);
+    p.drop_indent_block();
+    done
+}
+
+// test where
+// where IntOrd Ord[int] =
+//     let compare x y = x - y
+pub(super) fn where_(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    let block = p.new_indent_block();
+    p.bump(T![where]);
+    name(p);
+    constraints::opt_constraint_list(p);
+    if paths::is_path_start(p) {
+        paths::path(p);
+    } else {
+        p.error("expected a path");
+    }
+    if p.expect(T![=]) {
+        functions(p, block);
+    }
+    let done = m.complete(p, WHERE);
+    p.drop_indent_block();
+    done
+}
+
+/// The `let` declarations of a `class` or a `where`, indented past the
+/// keyword at column `block`.
+fn functions(p: &mut Parser<'_>, block: u32) {
+    if p.current().col <= block {
+        return;
+    }
+
+    p.new_indent_block();
+    p.many_same(function);
+    p.drop_indent_block();
+}
+
+fn function(p: &mut Parser<'_>) {
+    let m = p.start();
+    attrs(p);
+    if p.at(T![let]) {
+        let_decl(p, m);
+    } else {
+        m.abandon(p);
+        p.err_recover("expected a `let` declaration", ITEM_RECOVERY_SET);
+    }
+}
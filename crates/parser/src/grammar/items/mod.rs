@@ -1,8 +1,13 @@
+mod adt;
+mod classes;
+
 use crate::{
-    SyntaxKind::{EOF, LET_DECL, MODULE},
-    T,
-    grammar::name,
-    parser::{Marker, Parser},
+    ErrorCode, ParseError,
+    SyntaxKind::*,
+    T, TokenRange,
+    grammar::{constraints, expressions, name, params, paths, patterns, types},
+    parser::{self, CompletedMarker, Marker, Parser},
+    token_set::TokenSet,
 };
 
 // fn test() {
@@ -18,59 +23,182 @@ use crate::{
 // with_indent_block!(p.many_same(item))
 // let guard = mutex.lock().unwrap()
 
+// test_err item_trailing_tokens
+// let f x then
+// let x = f x |> g
+// union U = A | B of int
+// let y =
+//   1 )
 pub(super) fn module_contents(p: &mut Parser<'_>) {
     p.new_indent_block();
 
-    p.many_same(item);
+    p.many_same(|p| {
+        item(p);
+    });
 
     p.drop_indent_block();
 }
 
-fn item(p: &mut Parser<'_>) {
+/// Items are recovered line by line: after a broken item, parsing resumes at
+/// the next line of the block.
+const ITEM_RECOVERY_SET: TokenSet = TokenSet::EMPTY;
+
+// test_err item_recovery
+// let x
+// ) 92 x
+// let y
+pub(super) fn item(p: &mut Parser<'_>) -> Option<CompletedMarker> {
     let m = p.start();
+    attrs(p);
 
-    match p.current().kind {
-        T![let] => let_(p, m),
+    let done = match p.current().kind {
+        T![let] => let_decl(p, m),
         T![module] => module(p, m),
+        T![import] => import(p, m),
+        T![attribute] => attr_def(p, m),
+        T![class] => classes::class(p, m),
+        T![where] => classes::where_(p, m),
+        T![union] | T![value] | T![alias] => adt::adt_list(p, m),
         _ => {
             m.abandon(p);
-            p.error("expected item");
-            p.bump_any();
+            p.err_recover("expected an item", ITEM_RECOVERY_SET);
+            return None;
         },
+    };
+    Some(done)
+}
+
+fn attrs(p: &mut Parser<'_>) {
+    while p.at(ATTRIBUTE) {
+        let m = p.start();
+        p.bump(ATTRIBUTE);
+        m.complete(p, ATTR);
     }
 }
 
-fn let_(p: &mut Parser<'_>, m: Marker) {
+// test let_decl
+// let [Eq a] (=) x y = eq x y
+// let rec f (x : int) : int = f x
+// let (a, b) = pair
+// [<Inline>]
+// let g
+// test let_decl_glued_op
+// let (<>) x y = x
+// let (::) x xs = Cons(x, xs)
+pub(super) fn let_decl(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    let let_range = p.current_range();
     p.bump(T![let]);
+    p.eat(T![rec]);
+    constraints::opt_constraint_list(p);
+
+    let op = expressions::nth_operator(p, 1);
+    if p.nth_at(0, T!['('])
+        && let Some(op) = op
+        && p.nth_at(1 + parser::n_raw_tokens(op), T![')'])
+    {
+        let op = p.start();
+        p.bump(T!['(']);
+        expressions::operator(p);
+        p.bump(T![')']);
+        op.complete(p, LET_OP);
+    } else if p.nth_at(0, IDENT)
+        && !matches!(p.nth(1).kind, T![.] | T![,] | T![as])
+        && !p.nth_at(1, T![::])
+    {
+        // A lone name is a function or a variable, `f x` has a parameter
+        // rather than being a `f(x)` pattern.
+        patterns::ident_pat(p);
+    } else if patterns::is_pat_start(p) {
+        patterns::pattern(p);
+    } else {
+        p.error("expected a pattern");
+    }
 
-    name(p);
+    // `let f [Eq a] x`: the constraints are in the wrong place, but there is
+    // nothing else a `[` could mean here.
+    if p.at(T!['[']) {
+        let start = p.current_range().start;
+        constraints::brackets(p, constraints::Brackets::Constraints);
+        let range = TokenRange::new(start, p.current_range().start);
+        let err = ParseError::new(
+            ErrorCode::MisplacedConstraints,
+            "misplaced constraints",
+            range,
+        )
+        .with_label(let_range, "constraints must directly follow this `let`")
+        .with_note("write them as in `let [Eq a] f`");
+        p.push_error(err);
+    }
 
-    // parens(p);
+    params::opt_param_list(p);
+
+    if p.eat(T![:]) {
+        types::type_(p);
+    }
 
     if p.eat(T![=]) {
-        // parse expr
+        expressions::body(p);
     }
 
-    m.complete(p, LET_DECL);
+    m.complete(p, LET_DECL)
 }
 
-fn module(p: &mut Parser<'_>, m: Marker) {
+// test module
+// module M =
+//     let x
+//     module N
+//         =
+//         let y
+fn module(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
     let block = p.new_indent_block();
+    let module_range = p.current_range();
     p.bump(T![module]);
 
-    // TODO: >= relation for terminals' indentation??
+    // The name and `=` may go on continuation lines, indented past `module`.
+    if p.at(IDENT) {
+        p.check_continuation();
+    }
     name(p);
-
+    if p.at(T![=]) {
+        p.check_continuation();
+    }
     p.expect(T![=]);
 
     if p.current().col <= block {
-        p.error("module should contain at least one item");
-        m.complete(p, MODULE);
-        return;
+        let err = ParseError::new(
+            ErrorCode::EmptyModule,
+            "module should contain at least one item",
+            module_range,
+        );
+        p.push_error(err);
+        let done = m.complete(p, MODULE);
+        p.drop_indent_block();
+        return done;
     }
 
     module_contents(p);
 
-    m.complete(p, MODULE);
+    let done = m.complete(p, MODULE);
     p.drop_indent_block();
+    done
+}
+
+// test import
+// import Std.List
+fn import(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    p.bump(T![import]);
+    if paths::is_path_start(p) {
+        paths::plain_path(p);
+    } else {
+        p.error("expected a path");
+    }
+    m.complete(p, IMPORT)
+}
+
+// test attr_def
+// attribute Inline
+fn attr_def(p: &mut Parser<'_>, m: Marker) -> CompletedMarker {
+    p.bump(T![attribute]);
+    name(p);
+    m.complete(p, ATTR_DEF)
 }
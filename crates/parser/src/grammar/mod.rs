@@ -1,9 +1,17 @@
+mod constraints;
+mod expressions;
 mod items;
+mod params;
+mod paths;
+mod patterns;
+mod types;
 
 use crate::{
+    ErrorCode, ParseError,
     SyntaxKind::{self, *},
-    T,
-    parser::{Marker, Parser},
+    T, TokenRange,
+    parser::{CompletedMarker, Marker, Parser},
+    token_set::TokenSet,
 };
 
 pub(crate) mod entry {
@@ -12,10 +20,97 @@ pub(crate) mod entry {
     pub(crate) mod top {
         use super::*;
 
+        // test_err source_file_trailing_input
+        //   let x
+        // let y
         pub(crate) fn source_file(p: &mut Parser<'_>) {
             let m = p.start();
+            preamble(p);
             items::module_contents(p);
-            m.complete(p, MODULE);
+            if !p.at(EOF) {
+                // Lines to the left of the first item, as in `  let x\nlet y`.
+                let rest = p.start();
+                trailing_input(p);
+                rest.complete(p, ERROR);
+            }
+            m.complete(p, SOURCE_FILE);
+        }
+
+        pub(crate) fn expr(p: &mut Parser<'_>) {
+            whole(p, expressions::expr);
+        }
+
+        pub(crate) fn type_(p: &mut Parser<'_>) {
+            whole(p, types::type_);
+        }
+
+        pub(crate) fn pattern(p: &mut Parser<'_>) {
+            whole(p, patterns::pattern);
+        }
+
+        pub(crate) fn item(p: &mut Parser<'_>) {
+            whole(p, items::item);
+        }
+
+        // module Std.List
+        fn preamble(p: &mut Parser<'_>) {
+            // `module M =` is a nested module, not a preamble.
+            if !(p.nth_at(0, T![module]) && p.nth_at(1, IDENT) && !p.nth_at(2, T![=])) {
+                return;
+            }
+            let m = p.start();
+            p.bump(T![module]);
+            paths::plain_path(p);
+            m.complete(p, PREAMBLE);
+        }
+
+        /// Runs `f` on the whole input. If it doesn't parse exactly one node
+        /// spanning the input, everything is wrapped into an `ERROR` node, so
+        /// that the tree always has a single root.
+        fn whole(p: &mut Parser<'_>, f: fn(&mut Parser<'_>) -> Option<CompletedMarker>) {
+            let m = p.start();
+            let done = f(p);
+            if done.is_some() && p.at(EOF) {
+                m.abandon(p);
+                return;
+            }
+            trailing_input(p);
+            m.complete(p, ERROR);
+        }
+
+        /// Consumes the tokens left after the entry point, so that the tree
+        /// covers the whole text, and reports them.
+        fn trailing_input(p: &mut Parser<'_>) {
+            if p.at(EOF) {
+                return;
+            }
+            let err = p.new_error(ErrorCode::TrailingInput, "unexpected input");
+            let start = err.range.start;
+            while !p.at(EOF) {
+                p.bump_any();
+            }
+            let range = TokenRange::new(start, p.current_range().start);
+            p.push_error(ParseError { range, ..err });
+        }
+    }
+
+    pub(crate) mod prefix {
+        use super::*;
+
+        pub(crate) fn expr(p: &mut Parser<'_>) {
+            expressions::expr(p);
+        }
+
+        pub(crate) fn type_(p: &mut Parser<'_>) {
+            types::type_(p);
+        }
+
+        pub(crate) fn pattern(p: &mut Parser<'_>) {
+            patterns::pattern(p);
+        }
+
+        pub(crate) fn item(p: &mut Parser<'_>) {
+            items::item(p);
         }
     }
 }
@@ -26,6 +121,23 @@ fn name(p: &mut Parser<'_>) {
         p.bump(IDENT);
         m.complete(p, NAME);
     } else {
-        todo!("error recovery")
+        p.error("expected a name");
+    }
+}
+
+fn type_var(p: &mut Parser<'_>) {
+    let m = p.start();
+    p.bump(IDENT);
+    m.complete(p, TYPE_VAR);
+}
+
+/// One or more type variables, as in `class Functor f` or `[Eq a b]`.
+fn type_vars(p: &mut Parser<'_>) {
+    if !p.at(IDENT) {
+        p.error("expected a type variable");
+        return;
+    }
+    while p.at(IDENT) {
+        type_var(p);
     }
 }
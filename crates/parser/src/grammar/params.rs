@@ -0,0 +1,44 @@
+use super::*;
+use crate::grammar::{patterns, types};
+
+fn is_param_start(p: &Parser<'_>) -> bool {
+    patterns::is_pat_start(p)
+}
+
+// test param_list
+// let f x (y : int) { z } _ = x
+pub(super) fn opt_param_list(p: &mut Parser<'_>) {
+    if !is_param_start(p) || p.at_block_boundary() {
+        return;
+    }
+
+    let m = p.start();
+    while is_param_start(p) && !p.at_block_boundary() {
+        param(p);
+    }
+    m.complete(p, PARAM_LIST);
+}
+
+fn param(p: &mut Parser<'_>) {
+    let m = p.start();
+    match p.current().kind {
+        T!['('] => {
+            p.bump(T!['(']);
+            if !p.at(T![')']) {
+                patterns::pattern(p);
+                if p.eat(T![:]) {
+                    types::type_(p);
+                }
+            }
+            p.expect(T![')']);
+        },
+        // `f x (y)` has two parameters, not a `x(y)` pattern.
+        IDENT => {
+            patterns::ident_pat(p);
+        },
+        _ => {
+            patterns::pattern(p);
+        },
+    }
+    m.complete(p, PARAM);
+}
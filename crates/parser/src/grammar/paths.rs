@@ -0,0 +1,45 @@
+use super::*;
+use crate::grammar::constraints::{self, Brackets};
+
+pub(super) fn is_path_start(p: &Parser<'_>) -> bool {
+    p.nth_at(0, IDENT)
+}
+
+/// A path whose segments may carry type arguments, as in `Std.Option[int]`.
+pub(super) fn path(p: &mut Parser<'_>) {
+    path_inner(p, Mode::Type);
+}
+
+/// A path without type arguments, as in `import Std.List` or the class of a
+/// constraint.
+pub(super) fn plain_path(p: &mut Parser<'_>) {
+    path_inner(p, Mode::Plain);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Type,
+    Plain,
+}
+
+fn path_inner(p: &mut Parser<'_>, mode: Mode) {
+    assert!(is_path_start(p));
+    let path = p.start();
+    path_segment(p, mode);
+    let mut qual = path.complete(p, PATH);
+    while p.nth_at(0, T![.]) && p.nth(1).kind == IDENT {
+        let path = qual.precede(p);
+        p.bump(T![.]);
+        path_segment(p, mode);
+        qual = path.complete(p, PATH);
+    }
+}
+
+fn path_segment(p: &mut Parser<'_>, mode: Mode) {
+    let m = p.start();
+    name(p);
+    if mode == Mode::Type && p.at(T!['[']) {
+        constraints::brackets(p, Brackets::TypeArgs);
+    }
+    m.complete(p, PATH_SEGMENT);
+}
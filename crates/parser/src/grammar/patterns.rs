@@ -0,0 +1,238 @@
+use super::*;
+use crate::grammar::{expressions, paths, types};
+
+/// Tokens that follow a pattern in an enclosing construct.
+const PAT_RECOVERY_SET: TokenSet = TokenSet::new(&[
+    T![')'],
+    T!['}'],
+    T![,],
+    T![;],
+    T![|],
+    T![=],
+    T![:],
+    T![->],
+    T![when],
+]);
+
+pub(super) fn is_pat_start(p: &Parser<'_>) -> bool {
+    expressions::is_literal_start(p)
+        || matches!(p.current().kind, IDENT | T![_] | T!['('] | T!['{'])
+}
+
+pub(super) fn pattern(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    as_pat(p, true)
+}
+
+/// A pattern that stops before a top-level `,`, used where `,` separates
+/// fields, as in `Some(a, b)`.
+fn pattern_no_tuple(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    as_pat(p, false)
+}
+
+// test as_pat
+// let (a, b) as pair = p
+fn as_pat(p: &mut Parser<'_>, allow_tuple: bool) -> Option<CompletedMarker> {
+    let mut lhs = or_pat(p, allow_tuple)?;
+    while p.at(T![as]) {
+        let m = lhs.precede(p);
+        p.bump(T![as]);
+        name(p);
+        lhs = m.complete(p, IDENT_PAT);
+    }
+    Some(lhs)
+}
+
+// test or_pat
+// let f x =
+//     match x with
+//     | A | B -> 1
+fn or_pat(p: &mut Parser<'_>, allow_tuple: bool) -> Option<CompletedMarker> {
+    let first = tuple_pat(p, allow_tuple)?;
+    // `|}` closes an only-record pattern, as in `{| x = A | B |}`.
+    if !p.at(T![|]) || p.nth_at(0, T!["|}"]) {
+        return Some(first);
+    }
+
+    let m = first.precede(p);
+    while !p.nth_at(0, T!["|}"]) && p.eat(T![|]) {
+        if tuple_pat(p, allow_tuple).is_none() {
+            break;
+        }
+    }
+    Some(m.complete(p, OR_PAT))
+}
+
+// test tuple_pat
+// let a, b = p
+fn tuple_pat(p: &mut Parser<'_>, allow_tuple: bool) -> Option<CompletedMarker> {
+    let first = cons_pat(p)?;
+    if !allow_tuple || !p.at(T![,]) {
+        return Some(first);
+    }
+
+    let m = first.precede(p);
+    while p.eat(T![,]) {
+        if cons_pat(p).is_none() {
+            break;
+        }
+    }
+    Some(m.complete(p, TUPLE_PAT))
+}
+
+// test cons_pat
+// let x :: xs = l
+fn cons_pat(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    let head = atom_pat(p)?;
+    if !p.at(T![::]) {
+        return Some(head);
+    }
+
+    let m = head.precede(p);
+    p.bump(T![::]);
+    cons_pat(p);
+    Some(m.complete(p, CONS_PAT))
+}
+
+// test_err atom_pat_recovery
+// let f x =
+//     match x with
+//     | ] -> 1
+//     | _ -> 0
+fn atom_pat(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    if expressions::is_literal_start(p) {
+        let m = p.start();
+        expressions::literal(p);
+        return Some(m.complete(p, LITERAL_PAT));
+    }
+
+    let done = match p.current().kind {
+        T![_] => {
+            let m = p.start();
+            p.bump(T![_]);
+            m.complete(p, WILDCARD_PAT)
+        },
+        T!['('] => paren_pat(p),
+        T!['{'] if p.nth_at(0, T!["{|"]) => only_record_pat(p),
+        T!['{'] => record_pat(p),
+        IDENT if matches!(p.nth(1).kind, T![.] | T!['['] | T!['(']) => tuple_value_pat(p),
+        IDENT => ident_pat(p),
+        _ if p.at_block_boundary() => {
+            p.error("expected a pattern");
+            return None;
+        },
+        _ => {
+            p.err_recover("expected a pattern", PAT_RECOVERY_SET);
+            return None;
+        },
+    };
+    Some(done)
+}
+
+pub(super) fn ident_pat(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    name(p);
+    m.complete(p, IDENT_PAT)
+}
+
+// test paren_pat
+// let () = ()
+// let (x : int) = 1
+// test_err paren_pat_unclosed
+// let f (x : Std.int = x
+fn paren_pat(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    p.bump(T!['(']);
+    if !p.at(T![')'])
+        && let Some(pat) = pattern(p)
+        && p.at(T![:])
+    {
+        let asc = pat.precede(p);
+        p.bump(T![:]);
+        types::type_(p);
+        asc.complete(p, ASC_PAT);
+    }
+    p.expect(T![')']);
+    m.complete(p, PAREN_PAT)
+}
+
+// test record_pat
+// let { x; y = Some(z) } = r
+fn record_pat(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    p.bump(T!['{']);
+    while !p.at(EOF) && !p.at(T!['}']) {
+        if !is_pat_start(p) {
+            p.error("expected a record field");
+            break;
+        }
+        record_pat_field(p, true);
+        if !p.at(T!['}']) && !p.expect(T![;]) {
+            break;
+        }
+    }
+    p.expect(T!['}']);
+    m.complete(p, RECORD_PAT)
+}
+
+// test only_record_pat
+// let {| x, y = Some(z) |} = r
+// let f r =
+//     match r with
+//     | {|x = A | B|} -> 1
+//     | {| x, y = (a, b) |} -> 2
+// test_err only_record_pat_split
+// let { x = A |} = r
+// let {  | x |} = r
+// let f r =
+//     match r with
+//     | { x = A || B } -> 1
+fn only_record_pat(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    p.bump(T!["{|"]);
+    while !p.at(EOF) && !p.at(T!["|}"]) {
+        if !is_pat_start(p) {
+            p.error("expected a record field");
+            break;
+        }
+        record_pat_field(p, false);
+        if !p.at(T!["|}"]) && !p.expect(T![,]) {
+            break;
+        }
+    }
+    p.expect(T!["|}"]);
+    m.complete(p, ONLY_RECORD_PAT)
+}
+
+/// A field of a record pattern; `allow_tuple` is false where `,` separates
+/// the fields.
+fn record_pat_field(p: &mut Parser<'_>, allow_tuple: bool) {
+    let m = p.start();
+    if p.nth_at(0, IDENT) && p.nth(1).kind == T![=] {
+        name(p);
+        p.bump(T![=]);
+    }
+    as_pat(p, allow_tuple);
+    m.complete(p, RECORD_PAT_FIELD);
+}
+
+// test tuple_value_pat
+// let Some(x) = o
+// let Option.Some(a, b) = o
+fn tuple_value_pat(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    paths::path(p);
+    if p.eat(T!['(']) {
+        while !p.at(EOF) && !p.at(T![')']) {
+            if !is_pat_start(p) {
+                p.error("expected a pattern");
+                break;
+            }
+            pattern_no_tuple(p);
+            if !p.at(T![')']) && !p.expect(T![,]) {
+                break;
+            }
+        }
+        p.expect(T![')']);
+    }
+    m.complete(p, TUPLE_VALUE_PAT)
+}
@@ -0,0 +1,93 @@
+use super::*;
+use crate::grammar::{
+    constraints::{self, Brackets},
+    paths,
+};
+
+/// Tokens that follow a type in an enclosing construct.
+const TYPE_RECOVERY_SET: TokenSet =
+    TokenSet::new(&[T![')'], T![']'], T![,], T![=], T![|], T![and]]);
+
+pub(super) fn is_type_start(p: &Parser<'_>) -> bool {
+    matches!(p.current().kind, IDENT | T!['('] | T!['['] | T![_])
+}
+
+pub(super) fn type_(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    type_with(p, true)
+}
+
+/// A type that stops before a top-level `*`, used where `*` separates
+/// fields, as in `value Pair [a b] (a * b)`.
+pub(super) fn type_no_tuple(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    type_with(p, false)
+}
+
+// test fn_type
+// let f : (int -> Option[a]) -> a * b = g
+// test_err fn_type_split_arrow
+// let f : int - > int
+fn type_with(p: &mut Parser<'_>, allow_tuple: bool) -> Option<CompletedMarker> {
+    if p.at(T!['[']) {
+        let m = p.start();
+        constraints::brackets(p, Brackets::Constraints);
+        type_with(p, allow_tuple);
+        return Some(m.complete(p, CONSTRAINT_TYPE));
+    }
+
+    let lhs = tuple_type(p, allow_tuple)?;
+    if !p.at(T![->]) {
+        return Some(lhs);
+    }
+
+    let m = lhs.precede(p);
+    p.bump(T![->]);
+    type_with(p, allow_tuple);
+    Some(m.complete(p, FN_TYPE))
+}
+
+fn tuple_type(p: &mut Parser<'_>, allow_tuple: bool) -> Option<CompletedMarker> {
+    let first = type_atom(p)?;
+    if !allow_tuple || !p.at(T![*]) {
+        return Some(first);
+    }
+
+    let m = first.precede(p);
+    while p.eat(T![*]) {
+        if type_atom(p).is_none() {
+            break;
+        }
+    }
+    Some(m.complete(p, TUPLE_TYPE))
+}
+
+// test_err type_atom_recovery
+// let f : int -> = g
+fn type_atom(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    let m = p.start();
+    let kind = match p.current().kind {
+        IDENT => {
+            paths::path(p);
+            PATH_TYPE
+        },
+        T!['('] => {
+            p.bump(T!['(']);
+            type_(p);
+            p.expect(T![')']);
+            PAREN_TYPE
+        },
+        T![_] => {
+            p.bump(T![_]);
+            INFER_TYPE
+        },
+        _ => {
+            m.abandon(p);
+            if p.at_block_boundary() {
+                p.error("expected a type");
+            } else {
+                p.err_recover("expected a type", TYPE_RECOVERY_SET);
+            }
+            return None;
+        },
+    };
+    Some(m.complete(p, kind))
+}
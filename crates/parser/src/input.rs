@@ -3,23 +3,54 @@ use crate::SyntaxKind::{self, *};
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Token {
     pub kind: SyntaxKind,
+    /// Zero-based line of the token.
+    pub line: u32,
+    /// Zero-based column of the token, in bytes.
     pub col: u32,
 }
 
+/// The tokens of the text to parse, without trivia.
+///
+/// Besides its position, every token records whether it is joint to the next
+/// one, that is, immediately followed by it with no trivia in between. The
+/// lexer splits punctuation like `->` and `::` into single characters, and the
+/// parser glues joint ones back where the grammar expects them.
 #[derive(Default)]
 pub struct Input {
     tokens: Vec<Token>,
+    joint: Vec<bool>,
 }
 
 impl Input {
+    pub fn with_capacity(n_tokens: usize) -> Input {
+        Input {
+            tokens: Vec::with_capacity(n_tokens),
+            joint: Vec::with_capacity(n_tokens),
+        }
+    }
+
     pub fn token(&self, idx: usize) -> Token {
-        self.tokens
-            .get(idx)
-            .copied()
-            .unwrap_or(Token { kind: EOF, col: 0 })
+        self.tokens.get(idx).copied().unwrap_or(Token {
+            kind: EOF,
+            line: 0,
+            col: 0,
+        })
+    }
+
+    pub fn push(&mut self, kind: SyntaxKind, line: u32, col: u32) {
+        self.tokens.push(Token { kind, line, col });
+        self.joint.push(false);
+    }
+
+    /// Marks the last pushed token as joint to the next one.
+    pub fn was_joint(&mut self) {
+        if let Some(joint) = self.joint.last_mut() {
+            *joint = true;
+        }
     }
 
-    pub fn push(&mut self, kind: SyntaxKind, col: u32) {
-        self.tokens.push(Token { kind, col });
+    /// Whether the token at `idx` is immediately followed by the next one.
+    pub fn is_joint(&self, idx: usize) -> bool {
+        self.joint.get(idx).copied().unwrap_or(false)
     }
 }
@@ -2,13 +2,16 @@ use std::{ops, ops::Not};
 
 use crate::{
     SyntaxKind::{self, *},
-    T,
+    T, TokenRange,
 };
 
 pub struct LexedStr<'a> {
     text: &'a str,
     kind: Vec<SyntaxKind>,
     start: Vec<u32>,
+    /// Indices of non-trivia tokens, that is, of the tokens of the parser's
+    /// [`crate::Input`].
+    pub(crate) input: Vec<u32>,
     error: Vec<LexError>,
 }
 
@@ -69,6 +72,23 @@ impl<'a> LexedStr<'a> {
         r.end - r.start
     }
 
+    /// The text covered by a range of parser input tokens, see
+    /// [`TokenRange`].
+    pub fn input_text_range(&self, range: TokenRange) -> ops::Range<usize> {
+        if range.is_empty() {
+            let pos = match range.start.checked_sub(1) {
+                Some(prev) => self.text_range(self.input[prev as usize] as usize).end,
+                None => 0,
+            };
+            return pos..pos;
+        }
+        let lo = self.start[self.input[range.start as usize] as usize] as usize;
+        let hi = self
+            .text_range(self.input[range.end as usize - 1] as usize)
+            .end;
+        lo..hi
+    }
+
     pub fn error(&self, i: usize) -> Option<&str> {
         assert!(i < self.len());
         let err = self
@@ -85,6 +105,9 @@ impl<'a> LexedStr<'a> {
     }
 
     fn push(&mut self, kind: SyntaxKind, offset: usize) {
+        if !kind.is_trivia() {
+            self.input.push(self.kind.len() as u32);
+        }
         self.kind.push(kind);
         self.start.push(offset as u32);
     }
@@ -107,6 +130,7 @@ impl<'a> Converter<'a> {
                 text,
                 kind: Vec::new(),
                 start: Vec::new(),
+                input: Vec::new(),
                 error: Vec::new(),
             },
             offset: 0,
@@ -133,15 +157,58 @@ impl<'a> Converter<'a> {
         let mut err = "";
 
         let syntax_kind = match kind {
+            lexer::TokenKind::Alias => T![alias],
+            lexer::TokenKind::And => T![and],
+            lexer::TokenKind::As => T![as],
+            lexer::TokenKind::Attribute => T![attribute],
+            lexer::TokenKind::Class => T![class],
+            lexer::TokenKind::Else => T![else],
+            lexer::TokenKind::False => T![false],
+            lexer::TokenKind::If => T![if],
+            lexer::TokenKind::Import => T![import],
+            lexer::TokenKind::In => T![in],
             lexer::TokenKind::Let => T![let],
+            lexer::TokenKind::Match => T![match],
             lexer::TokenKind::Module => T![module],
+            lexer::TokenKind::Rec => T![rec],
+            lexer::TokenKind::Then => T![then],
+            lexer::TokenKind::True => T![true],
+            lexer::TokenKind::Union => T![union],
+            lexer::TokenKind::Value => T![value],
+            lexer::TokenKind::When => T![when],
+            lexer::TokenKind::Where => T![where],
+            lexer::TokenKind::With => T![with],
             lexer::TokenKind::Eq => T![=],
+            lexer::TokenKind::Lt => T![<],
+            lexer::TokenKind::Gt => T![>],
+            lexer::TokenKind::And2 => T![&&],
+            lexer::TokenKind::Plus => T![+],
+            lexer::TokenKind::Minus => T![-],
+            lexer::TokenKind::Slash => T![/],
+            lexer::TokenKind::Percent => T![%],
+            lexer::TokenKind::Comma => T![,],
+            lexer::TokenKind::Semicolon => T![;],
+            lexer::TokenKind::Dot => T![.],
+            lexer::TokenKind::Colon => T![:],
+            lexer::TokenKind::Star => T![*],
+            lexer::TokenKind::Underscore => T![_],
+            lexer::TokenKind::Pipe => T![|],
             lexer::TokenKind::Ident => IDENT,
+            lexer::TokenKind::AttributeUse => ATTRIBUTE,
             lexer::TokenKind::Whitespace => WHITESPACE,
+            lexer::TokenKind::Comment => COMMENT,
             lexer::TokenKind::Newline => NEWLINE,
-            lexer::TokenKind::OpenParen => todo!(),
-            lexer::TokenKind::CloseParen => todo!(),
+            lexer::TokenKind::OpenParen => T!['('],
+            lexer::TokenKind::CloseParen => T![')'],
+            lexer::TokenKind::OpenBracket => T!['['],
+            lexer::TokenKind::CloseBracket => T![']'],
+            lexer::TokenKind::OpenCurly => T!['{'],
+            lexer::TokenKind::CloseCurly => T!['}'],
             lexer::TokenKind::Number => INT_NUMBER,
+            lexer::TokenKind::Float => FLOAT_NUMBER,
+            lexer::TokenKind::String => STRING,
+            lexer::TokenKind::FString => F_STRING,
+            lexer::TokenKind::Char => CHAR,
             lexer::TokenKind::Unknown => ERROR,
             lexer::TokenKind::Eof => EOF,
         };
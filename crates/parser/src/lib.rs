@@ -1,5 +1,6 @@
 #![allow(unused)]
 
+mod error;
 mod event;
 mod grammar;
 mod input;
@@ -8,8 +9,12 @@ mod output;
 mod parser;
 mod shortcuts;
 mod syntax_kind;
+mod token_set;
+#[cfg(test)]
+mod tests;
 
 pub use crate::{
+    error::{ErrorCode, ErrorLabel, ParseError, TokenRange},
     input::Input,
     lexed_str::LexedStr,
     output::{Output, Step},
@@ -17,14 +22,53 @@ pub use crate::{
     syntax_kind::SyntaxKind,
 };
 
+/// Parse a prefix of the input as a particular syntactic construct.
+///
+/// Unlike [`TopEntryPoint`], the parser stops as soon as the construct is
+/// complete, and the rest of the input is left alone. Use
+/// [`Output::n_input_tokens`] to find out where it stopped.
+pub enum PrefixEntryPoint {
+    Expr,
+    Type,
+    Pattern,
+    Item,
+}
+
+impl PrefixEntryPoint {
+    pub fn parse(&self, input: &Input) -> Output {
+        let entry_point: fn(&'_ mut parser::Parser<'_>) = match self {
+            PrefixEntryPoint::Expr => grammar::entry::prefix::expr,
+            PrefixEntryPoint::Type => grammar::entry::prefix::type_,
+            PrefixEntryPoint::Pattern => grammar::entry::prefix::pattern,
+            PrefixEntryPoint::Item => grammar::entry::prefix::item,
+        };
+        let mut p = parser::Parser::new(input);
+        entry_point(&mut p);
+        let events = p.finish();
+        event::process(events)
+    }
+}
+
+/// Parse the whole input as a particular syntactic construct.
+///
+/// The resulting tree always has a single root. If the input is not exactly
+/// one construct of the requested kind, the root is an `ERROR` node.
 pub enum TopEntryPoint {
     SourceFile,
+    Expr,
+    Type,
+    Pattern,
+    Item,
 }
 
 impl TopEntryPoint {
     pub fn parse(&self, input: &Input) -> Output {
         let entry_point: fn(&'_ mut parser::Parser<'_>) = match self {
             TopEntryPoint::SourceFile => grammar::entry::top::source_file,
+            TopEntryPoint::Expr => grammar::entry::top::expr,
+            TopEntryPoint::Type => grammar::entry::top::type_,
+            TopEntryPoint::Pattern => grammar::entry::top::pattern,
+            TopEntryPoint::Item => grammar::entry::top::item,
         };
 
         let mut p = parser::Parser::new(input);
@@ -1,6 +1,6 @@
 //! See [`Output`]
 
-use crate::SyntaxKind;
+use crate::{ParseError, SyntaxKind};
 
 /// Output of the parser -- a DFS traversal of a concrete syntax tree.
 ///
@@ -21,7 +21,7 @@ pub struct Output {
     /// |16 bit kind|8 bit n_input_tokens|4 bit tag|4 bit leftover|
     /// ``````
     event: Vec<u32>,
-    error: Vec<String>,
+    error: Vec<ParseError>,
 }
 
 #[derive(Debug)]
@@ -35,7 +35,7 @@ pub enum Step<'a> {
     },
     Exit,
     Error {
-        msg: &'a str,
+        err: &'a ParseError,
     },
 }
 
@@ -58,7 +58,7 @@ impl Output {
         self.event.iter().map(|&event| {
             if event & Self::EVENT_MASK == 0 {
                 return Step::Error {
-                    msg: self.error[(event as usize) >> Self::ERROR_SHIFT].as_str(),
+                    err: &self.error[(event as usize) >> Self::ERROR_SHIFT],
                 };
             }
             let tag = ((event & Self::TAG_MASK) >> Self::TAG_SHIFT) as u8;
@@ -84,6 +84,26 @@ impl Output {
         })
     }
 
+    /// The number of input tokens consumed by the parser, that is, how far a
+    /// [`crate::PrefixEntryPoint`] got.
+    pub fn n_input_tokens(&self) -> usize {
+        self.iter()
+            .map(|step| {
+                match step {
+                    Step::Token { n_input_tokens, .. } => n_input_tokens as usize,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    pub(crate) fn with_capacity(n_events: usize) -> Output {
+        Output {
+            event: Vec::with_capacity(n_events),
+            error: Vec::new(),
+        }
+    }
+
     pub(crate) fn token(&mut self, kind: SyntaxKind, n_tokens: u8) {
         let e = ((kind as u16 as u32) << Self::KIND_SHIFT)
             | ((n_tokens as u32) << Self::N_INPUT_TOKEN_SHIFT)
@@ -103,7 +123,7 @@ impl Output {
         self.event.push(e)
     }
 
-    pub(crate) fn error(&mut self, error: String) {
+    pub(crate) fn error(&mut self, error: ParseError) {
         let idx = self.error.len();
         self.error.push(error);
         let e = (idx as u32) << Self::ERROR_SHIFT;
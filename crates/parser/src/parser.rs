@@ -1,22 +1,103 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 
 use drop_bomb::DropBomb;
 
 use crate::{
     SyntaxKind::{self, *},
+    T,
+    error::{self, ErrorCode, ParseError, TokenRange},
     event::Event,
     input::{Input, Token},
+    token_set::TokenSet,
 };
 
 pub struct Parser<'t> {
     input: &'t Input,
     pos: usize,
     events: Vec<Event>,
+    /// The number of events when the last token was consumed.
+    last_bump: usize,
+    /// Lookahead since the last token was consumed, to catch a grammar loop
+    /// that only looks ahead. It is only counted in debug builds, as it is on
+    /// the hot path.
+    #[cfg(debug_assertions)]
     steps: Cell<u32>,
-    indentation_blocks: Vec<u32>,
+    /// Kinds tested with `at` since the last bump, in the order they were
+    /// tested, for "expected one of ..." messages.
+    expected: RefCell<Vec<SyntaxKind>>,
+    /// The same kinds as `expected`, to test for duplicates quickly.
+    expected_set: Cell<TokenSet>,
+    indentation_blocks: Vec<Context>,
 }
 
-const PARSER_STEP_LIMIT: usize = 15_000_000;
+/// An indentation context, opened by the token at `pos`. Lines continuing the
+/// construct must be indented past `col`.
+///
+/// The offside rule, as implemented by the parser:
+///
+/// - A construct continues only on tokens strictly to the right of the
+///   innermost context, see [`Parser::at_block_boundary`].
+/// - A line that starts strictly between a block and the enclosing context
+///   can't continue the block nor start something in the enclosing context, so
+///   it is offside of the block, see [`Parser::drop_indent_block`]. Closing
+///   `)`, `]` and `}` are exempt and may go down to the column of the enclosing
+///   context.
+/// - Keywords that continue a construct (`then`, `else`, `with`, `|`) may be
+///   aligned with the keyword that started it, but not placed to the left of
+///   it. Such constructs open a keyword context, which is not a block: the
+///   expression around them may continue to the left of the keyword.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Context {
+    pos: u32,
+    kind: SyntaxKind,
+    line: u32,
+    pub(crate) col: u32,
+    is_block: bool,
+}
+
+/// Tokens that close a delimited construct, allowed at the column of the
+/// context it started in.
+const CLOSING: TokenSet = TokenSet::new(&[R_PAREN, R_BRACKET, R_CURLY]);
+
+/// Punctuation that the lexer leaves split into single characters, with the
+/// parts it is glued from when they are joint. The parts are glued only where
+/// the grammar asks for the composite token, with [`Parser::at`] and the
+/// like, so that `|` `|` in a pattern stays two `|`.
+const COMPOSITES: [(SyntaxKind, [SyntaxKind; 2]); 8] = [
+    (T![->], [T![-], T![>]]),
+    (T![::], [T![:], T![:]]),
+    (T![<>], [T![<], T![>]]),
+    (T![<=], [T![<], T![=]]),
+    (T![>=], [T![>], T![=]]),
+    (T![||], [T![|], T![|]]),
+    (T!["{|"], [T!['{'], T![|]]),
+    (T!["|}"], [T![|], T!['}']]),
+];
+
+/// The parts that a token of `kind` is glued from, if it is a composite.
+fn composite_parts(kind: SyntaxKind) -> Option<[SyntaxKind; 2]> {
+    COMPOSITES
+        .iter()
+        .find(|&&(it, _)| it == kind)
+        .map(|&(_, parts)| parts)
+}
+
+/// The number of input tokens a token of `kind` is glued from.
+pub(crate) fn n_raw_tokens(kind: SyntaxKind) -> usize {
+    match composite_parts(kind) {
+        Some(_) => 2,
+        None => 1,
+    }
+}
+
+/// The number of events the parser may push without consuming a token before
+/// it is considered stuck. Every grammar loop either consumes a token or
+/// starts a node or reports an error, so a stuck loop hits this.
+const STUCK_EVENT_LIMIT: usize = 100_000;
+
+/// The same for lookahead, in debug builds.
+#[cfg(debug_assertions)]
+const STUCK_STEP_LIMIT: u32 = 15_000_000;
 
 impl<'t> Parser<'t> {
     pub fn new(input: &'t Input) -> Parser<'t> {
@@ -24,8 +105,18 @@ impl<'t> Parser<'t> {
             input,
             pos: 0,
             events: Vec::new(),
+            last_bump: 0,
+            #[cfg(debug_assertions)]
             steps: Cell::new(0),
-            indentation_blocks: vec![0],
+            expected: RefCell::new(Vec::new()),
+            expected_set: Cell::new(TokenSet::EMPTY),
+            indentation_blocks: vec![Context {
+                pos: 0,
+                kind: EOF,
+                line: 0,
+                col: 0,
+                is_block: true,
+            }],
         }
     }
 
@@ -37,28 +128,67 @@ impl<'t> Parser<'t> {
         self.nth(0)
     }
 
+    /// The input token `n` tokens ahead. Joint punctuation is not glued
+    /// here: at `->`, this is `-`.
     pub fn nth(&self, n: usize) -> Token {
         assert!(n <= 3);
-
-        let steps = self.steps.get();
-        assert!(
-            (steps as usize) < PARSER_STEP_LIMIT,
-            "the parser seems stuck"
-        );
-        self.steps.set(steps + 1);
-
+        self.step();
         self.input.token(self.pos + n)
     }
 
+    /// Checks if the current token is `kind`, and records `kind` for the
+    /// message of [`Parser::expect`] if it fails at this token. Lookahead that
+    /// only tells constructs apart, rather than accepting `kind` here,
+    /// should use [`Parser::nth_at`].
     pub fn at(&self, kind: SyntaxKind) -> bool {
+        let expected = self.expected_set.get();
+        if kind != EOF && !expected.contains(kind) {
+            self.expected_set
+                .set(expected.union(TokenSet::new(&[kind])));
+            self.expected.borrow_mut().push(kind);
+        }
         self.nth_at(0, kind)
     }
 
-    fn nth_at(&self, n: usize, kind: SyntaxKind) -> bool {
-        self.input.token(self.pos + n).kind == kind
+    /// Checks if the current token is in `kinds`, gluing it with the next
+    /// one for the composites in `kinds`.
+    pub(crate) fn at_ts(&self, kinds: TokenSet) -> bool {
+        kinds.contains(self.input.token(self.pos).kind)
+            || COMPOSITES
+                .iter()
+                .any(|&(it, _)| kinds.contains(it) && self.nth_at(0, it))
+    }
+
+    /// Checks if the token `n` input tokens ahead is `kind`. A composite
+    /// `kind` is there if its parts are, joint.
+    pub(crate) fn nth_at(&self, n: usize, kind: SyntaxKind) -> bool {
+        self.step();
+        let idx = self.pos + n;
+        match composite_parts(kind) {
+            Some([first, second]) => {
+                self.input.token(idx).kind == first
+                    && self.input.is_joint(idx)
+                    && self.input.token(idx + 1).kind == second
+            },
+            None => self.input.token(idx).kind == kind,
+        }
+    }
+
+    /// Counts a lookahead, see `STUCK_STEP_LIMIT`.
+    fn step(&self) {
+        #[cfg(debug_assertions)]
+        {
+            let steps = self.steps.get();
+            assert!(steps < STUCK_STEP_LIMIT, "the parser seems stuck");
+            self.steps.set(steps + 1);
+        }
     }
 
     fn push_event(&mut self, event: Event) {
+        assert!(
+            self.events.len() - self.last_bump < STUCK_EVENT_LIMIT,
+            "the parser seems stuck"
+        );
         self.events.push(event);
     }
 
@@ -77,74 +207,268 @@ impl<'t> Parser<'t> {
             return false;
         }
 
-        let n_raw_tokens = 1;
-
-        self.do_bump(kind, n_raw_tokens);
+        self.do_bump(kind, n_raw_tokens(kind) as u8);
         true
     }
 
     pub fn do_bump(&mut self, kind: SyntaxKind, n_raw_tokens: u8) {
         self.pos += n_raw_tokens as usize;
-        self.steps.set(0);
+        self.expected.borrow_mut().clear();
+        self.expected_set.set(TokenSet::EMPTY);
         self.push_event(Event::Token { kind, n_raw_tokens });
+        self.last_bump = self.events.len();
+        #[cfg(debug_assertions)]
+        self.steps.set(0);
     }
 
-    /// Advances the parser by one token
+    /// Advances the parser by one input token.
     pub(crate) fn bump_any(&mut self) {
-        let kind = self.nth(0).kind;
+        let kind = self.current().kind;
         if kind == EOF {
             return;
         }
         self.do_bump(kind, 1);
     }
 
-    /// Emit error with the `message`
-    /// FIXME: this should be much more fancy and support
-    /// structured errors with spans and notes, like rustc
-    /// does.
+    /// The range of the current token, empty at the end of input.
+    pub(crate) fn current_range(&self) -> TokenRange {
+        let pos = self.pos as u32;
+        if self.nth_at(0, EOF) {
+            TokenRange::empty(pos)
+        } else {
+            TokenRange::new(pos, pos + 1)
+        }
+    }
+
+    /// Emit an [`ErrorCode::UnexpectedToken`] error at the current token, as
+    /// in "`message`, found `then`".
     pub(crate) fn error<T: Into<String>>(&mut self, message: T) {
-        let msg = message.into();
-        self.push_event(Event::Error { msg });
+        self.push_error(self.unexpected(message));
+    }
+
+    /// Like [`Parser::error`], but returns the error so that labels and notes
+    /// can be added before [`Parser::push_error`].
+    pub(crate) fn unexpected<T: Into<String>>(&self, message: T) -> ParseError {
+        let message = format!("{}, found {}", message.into(), self.found());
+        self.new_error(ErrorCode::UnexpectedToken, message)
+    }
+
+    /// An error at the current token, to be completed with labels and notes
+    /// and emitted with [`Parser::push_error`].
+    pub(crate) fn new_error<T: Into<String>>(&self, code: ErrorCode, message: T) -> ParseError {
+        ParseError::new(code, message, self.current_range())
+    }
+
+    pub(crate) fn push_error(&mut self, err: ParseError) {
+        let err = Box::new(err);
+        self.push_event(Event::Error { err });
+    }
+
+    /// Report an unexpected token and skip ahead to a place where parsing can
+    /// resume: a token in `recovery`, or a token at or to the left of the
+    /// innermost indentation block, which starts the next line of that block
+    /// or of an enclosing one. Everything skipped ends up in a single `ERROR`
+    /// node, and the error covers all of it.
+    ///
+    /// Nothing is skipped if the current token is already in `recovery`.
+    pub(crate) fn err_recover(&mut self, message: &str, recovery: TokenSet) {
+        let err = self.unexpected(message);
+        if self.at(EOF) || self.at_ts(recovery) {
+            self.push_error(err);
+            return;
+        }
+
+        let m = self.start();
+        let start = err.range.start;
+        self.bump_any();
+        while !self.at(EOF) && !self.at_ts(recovery) && !self.at_block_boundary() {
+            self.bump_any();
+        }
+        m.complete(self, ERROR);
+
+        let range = TokenRange::new(start, self.pos as u32);
+        self.push_error(ParseError { range, ..err });
     }
 
-    /// Consume the next token if it is `kind` or emit an error
-    /// otherwise.
+    /// Consume the next token if it is `kind` or emit an error listing
+    /// everything that was tried at this position otherwise.
     pub(crate) fn expect(&mut self, kind: SyntaxKind) -> bool {
         if self.eat(kind) {
             return true;
         }
-        self.error(format!("expected {kind:?}"));
+        // A kind that was probed for but not taken here is not expected.
+        let expected: Vec<_> = self
+            .expected
+            .borrow()
+            .iter()
+            .copied()
+            .filter(|&it| !self.nth_at(0, it))
+            .collect();
+        let message = match expected.as_slice() {
+            [single] => format!("expected {}", error::describe(*single)),
+            _ => {
+                let kinds = expected.iter().map(|&it| error::describe(it));
+                format!("expected one of {}", kinds.collect::<Vec<_>>().join(", "))
+            },
+        };
+        self.error(message);
         false
     }
 
+    fn found(&self) -> String {
+        error::describe(self.nth(0).kind)
+    }
+
+    fn context(&self, is_block: bool) -> Context {
+        let Token { kind, line, col } = self.current();
+        let pos = self.pos as u32;
+        Context {
+            pos,
+            kind,
+            line,
+            col,
+            is_block,
+        }
+    }
+
     pub(crate) fn new_indent_block(&mut self) -> u32 {
-        let col = self.current().col;
-        self.indentation_blocks.push(col);
-        col
+        let ctx = self.context(true);
+        self.indentation_blocks.push(ctx);
+        ctx.col
     }
 
+    /// Opens a keyword context at the current token, as for `if` and `match`,
+    /// and returns it. Close it with [`Parser::drop_indent_block`].
+    pub(crate) fn new_keyword_context(&mut self) -> Context {
+        let ctx = self.context(false);
+        self.indentation_blocks.push(ctx);
+        ctx
+    }
+
+    /// Closes the innermost context. If it is a block and the next line starts
+    /// strictly between it and the enclosing context, that line is offside:
+    /// it is reported and skipped up to the next line that fits the enclosing
+    /// context.
     pub(crate) fn drop_indent_block(&mut self) {
-        self.indentation_blocks.pop();
+        let ctx = self.indentation_blocks.pop().unwrap();
+        let (outer, _) = self.get_current_indent_block();
+
+        let col = self.current().col;
+        if !ctx.is_block
+            || self.at(EOF)
+            || !self.at_line_start()
+            || self.at_ts(CLOSING)
+            || !(outer < col && col < ctx.col)
+        {
+            return;
+        }
+
+        let err = self.offside_error(ctx);
+        let m = self.start();
+        while !self.at(EOF) && self.current().col > outer {
+            self.bump_any();
+        }
+        m.complete(self, ERROR);
+        let range = TokenRange::new(err.range.start, self.pos as u32);
+        self.push_error(ParseError { range, ..err });
     }
 
     pub(crate) fn get_current_indent_block(&self) -> (u32, usize) {
         (
-            *self.indentation_blocks.last().unwrap(),
+            self.indentation_blocks.last().unwrap().col,
             self.indentation_blocks.len() - 1,
         )
     }
 
     pub(crate) fn get_indent_block(&self, index: usize) -> u32 {
-        *self.indentation_blocks.get(index).unwrap()
+        self.indentation_blocks.get(index).unwrap().col
+    }
+
+    /// Whether the current token is the first one on its line.
+    pub(crate) fn at_line_start(&self) -> bool {
+        self.pos == 0 || self.input.token(self.pos - 1).line != self.current().line
+    }
+
+    /// An error for the current token, which is offside of `ctx`.
+    pub(crate) fn offside_error(&self, ctx: Context) -> ParseError {
+        let what = match ctx.kind.text() {
+            Some(text) => format!("the `{text}`"),
+            None => "the context".to_owned(),
+        };
+        let message = format!(
+            "this token is offside of {what} started at {}:{}",
+            ctx.line + 1,
+            ctx.col + 1
+        );
+        self.new_error(ErrorCode::Offside, message).with_label(
+            TokenRange::new(ctx.pos, ctx.pos + 1),
+            "context started here",
+        )
+    }
+
+    /// Whether the current token is a `kind` that continues the construct of
+    /// the innermost context, a keyword context. It may be aligned with the
+    /// context, but not placed to the left of it.
+    ///
+    /// A token to the left of the context but to the right of the enclosing
+    /// one can't belong to any other construct, so it is taken with an
+    /// offside error. Anything further to the left is left for the enclosing
+    /// constructs.
+    pub(crate) fn at_aligned(&mut self, kind: SyntaxKind) -> bool {
+        if !self.at(kind) {
+            return false;
+        }
+        let (ctx, outer) = match self.indentation_blocks.as_slice() {
+            [.., outer, ctx] => (*ctx, outer.col),
+            _ => unreachable!("no keyword context"),
+        };
+        let col = self.current().col;
+        if col >= ctx.col {
+            return true;
+        }
+        if col <= outer {
+            return false;
+        }
+        self.push_error(self.offside_error(ctx));
+        true
     }
 
-    /// Zero or more at the same indentation level.
+    /// Reports the current token if it starts a line at or to the left of the
+    /// innermost context, where it can't continue the construct being parsed.
+    /// The token is still parsed as a part of that construct.
+    pub(crate) fn check_continuation(&mut self) {
+        if !self.at(EOF) && self.at_line_start() && self.at_block_boundary() {
+            let ctx = *self.indentation_blocks.last().unwrap();
+            self.push_error(self.offside_error(ctx));
+        }
+    }
+
+    /// Whether the current token lies at or to the left of the innermost
+    /// indentation block, so that it can't continue the construct being
+    /// parsed.
+    pub(crate) fn at_block_boundary(&self) -> bool {
+        let (block, _) = self.get_current_indent_block();
+        self.current().col <= block
+    }
+
+    /// Zero or more at the same indentation level. Lines indented past the
+    /// block, which can't start a new element, are skipped with an error, as
+    /// is what an element leaves over on its last line.
     pub(crate) fn many_same(&mut self, p: impl Fn(&mut Self)) {
         let (block, _) = self.get_current_indent_block();
         while !self.at(EOF) {
-            if self.current().col != block {
+            let col = self.current().col;
+            if col < block {
                 break;
             }
+            if col > block || !self.at_line_start() {
+                let message = match self.at_line_start() {
+                    true => format!("expected a line at column {}", block + 1),
+                    false => "expected the end of the line".to_owned(),
+                };
+                self.err_recover(&message, TokenSet::EMPTY);
+                continue;
+            }
             p(self);
         }
     }
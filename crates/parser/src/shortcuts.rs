@@ -1,7 +1,7 @@
 use std::mem;
 
 use crate::{
-    Input, LexedStr, Step,
+    Input, LexedStr, ParseError, Step,
     SyntaxKind::{self, *},
 };
 
@@ -10,28 +10,52 @@ pub enum StrStep<'a> {
     Token { kind: SyntaxKind, text: &'a str },
     Enter { kind: SyntaxKind },
     Exit,
-    Error { msg: &'a str, pos: usize },
+    Error { err: &'a ParseError },
 }
 
 impl LexedStr<'_> {
     pub fn to_input(&self) -> Input {
-        // annotate tokens with columns and skip trivia
-        let mut res = Input::default();
+        self.to_input_at(0, 0)
+    }
 
-        let mut col = 0;
+    /// Like [`LexedStr::to_input`], but for a text that starts at `line` and
+    /// `col` of a larger file, as when a part of the file is reparsed.
+    pub fn to_input_at(&self, mut line: u32, mut col: u32) -> Input {
+        // annotate tokens with lines and columns and skip trivia
+        let mut res = Input::with_capacity(self.input.len());
+        let mut was_joint = false;
 
         for i in 0..self.len() {
             let kind = self.kind(i);
 
-            if kind == NEWLINE {
-                col = 0;
+            if kind.is_trivia() {
+                was_joint = false;
             } else {
-                let text_len = self.text_len(i) as u32;
-
-                if !kind.is_trivia() {
-                    res.push(kind, col);
+                if was_joint {
+                    res.was_joint();
                 }
-                col += text_len;
+                res.push(kind, line, col);
+                was_joint = true;
+            }
+
+            // Only newlines, block comments and unknown characters may contain
+            // line breaks.
+            let text = self.text(i);
+            match kind {
+                NEWLINE => {
+                    line += 1;
+                    col = 0;
+                },
+                COMMENT | ERROR => {
+                    match text.rfind('\n') {
+                        Some(last) => {
+                            line += text.matches('\n').count() as u32;
+                            col = (text.len() - last - 1) as u32;
+                        },
+                        None => col += text.len() as u32,
+                    }
+                },
+                _ => col += text.len() as u32,
             }
         }
 
@@ -58,10 +82,7 @@ impl LexedStr<'_> {
                 } => builder.token(kind, n_raw_tokens),
                 Step::Enter { kind } => builder.enter(kind),
                 Step::Exit => builder.exit(),
-                Step::Error { msg } => {
-                    let text_pos = builder.lexed.text_start(builder.pos);
-                    (builder.sink)(StrStep::Error { msg, pos: text_pos });
-                },
+                Step::Error { err } => (builder.sink)(StrStep::Error { err }),
             }
         }
 
@@ -167,26 +188,35 @@ fn n_attached_trivias<'a>(
     trivias: impl Iterator<Item = (SyntaxKind, &'a str)>,
 ) -> usize {
     match kind {
-        LET_DECL | MODULE | CLASS => {
+        LET_DECL | MODULE | IMPORT | ATTR_DEF | CLASS | ADT_LIST | UNION | VALUE | ALIAS
+        | WHERE => {
             let mut res = 0;
-            let mut trivias = trivias.enumerate().peekable();
+            // Whether the trivias since the last comment are on one line, so
+            // that the next newline makes a blank line.
+            let mut line_break = false;
+            let mut trivias = trivias
+                .enumerate()
+                .filter(|(_, (kind, _))| *kind != WHITESPACE)
+                .peekable();
 
             while let Some((i, (kind, text))) = trivias.next() {
                 match kind {
-                    WHITESPACE if text.contains("\n\n") => {
-                        // we check whether the next token is a doc-comment
-                        // and skip the whitespace in this case
-                        if let Some((COMMENT, peek_text)) = trivias.peek().map(|(_, pair)| pair) {
-                            if is_outer(peek_text) {
-                                continue;
-                            }
+                    NEWLINE if line_break => {
+                        // A blank line separates the node from the comments
+                        // above it, unless they are docs.
+                        if let Some((_, (COMMENT, peek_text))) = trivias.peek()
+                            && is_outer(peek_text)
+                        {
+                            continue;
                         }
                         break;
                     },
+                    NEWLINE => line_break = true,
                     COMMENT => {
                         if is_inner(text) {
                             break;
                         }
+                        line_break = false;
                         res = i + 1;
                     },
                     _ => (),
@@ -17,14 +17,27 @@ pub enum SyntaxKind {
     R_CURLY,
     L_BRACKET,
     R_BRACKET,
+    L_ANGLE,
+    R_ANGLE,
     PIPE,
+    PLUS,
     STAR,
+    SLASH,
+    PERCENT,
     UNDERSCORE,
     DOT,
     COLON,
     COLON2,
     EQ,
+    NEQ,
+    MINUS,
     ARROW,
+    LTEQ,
+    GTEQ,
+    AMP2,
+    PIPE2,
+    L_CURLY_PIPE,
+    PIPE_R_CURLY,
     ALIAS_KW,
     AND_KW,
     AS_KW,
@@ -88,6 +101,7 @@ pub enum SyntaxKind {
     MATCH_GUARD,
     MODULE,
     NAME,
+    ONLY_RECORD_PAT,
     OPERATOR,
     OR_PAT,
     PARAM,
@@ -97,6 +111,7 @@ pub enum SyntaxKind {
     PAREN_TYPE,
     PAT,
     PATH,
+    PATH_EXPR,
     PATH_SEGMENT,
     PATH_TYPE,
     PREAMBLE,
@@ -159,14 +174,27 @@ impl SyntaxKind {
                 | R_CURLY
                 | L_BRACKET
                 | R_BRACKET
+                | L_ANGLE
+                | R_ANGLE
                 | PIPE
+                | PLUS
                 | STAR
+                | SLASH
+                | PERCENT
                 | UNDERSCORE
                 | DOT
                 | COLON
                 | COLON2
                 | EQ
+                | NEQ
+                | MINUS
                 | ARROW
+                | LTEQ
+                | GTEQ
+                | AMP2
+                | PIPE2
+                | L_CURLY_PIPE
+                | PIPE_R_CURLY
         )
     }
     pub fn is_literal(self) -> bool {
@@ -209,16 +237,78 @@ impl SyntaxKind {
             '}' => R_CURLY,
             '[' => L_BRACKET,
             ']' => R_BRACKET,
+            '<' => L_ANGLE,
+            '>' => R_ANGLE,
             '|' => PIPE,
+            '+' => PLUS,
             '*' => STAR,
+            '/' => SLASH,
+            '%' => PERCENT,
             '_' => UNDERSCORE,
             '.' => DOT,
             ':' => COLON,
             '=' => EQ,
+            '-' => MINUS,
             _ => return None,
         };
         Some(tok)
     }
+    pub fn text(self) -> Option<&'static str> {
+        let text = match self {
+            SEMICOLON => ";",
+            COMMA => ",",
+            L_PAREN => "(",
+            R_PAREN => ")",
+            L_CURLY => "{",
+            R_CURLY => "}",
+            L_BRACKET => "[",
+            R_BRACKET => "]",
+            L_ANGLE => "<",
+            R_ANGLE => ">",
+            PIPE => "|",
+            PLUS => "+",
+            STAR => "*",
+            SLASH => "/",
+            PERCENT => "%",
+            UNDERSCORE => "_",
+            DOT => ".",
+            COLON => ":",
+            COLON2 => "::",
+            EQ => "=",
+            NEQ => "<>",
+            MINUS => "-",
+            ARROW => "->",
+            LTEQ => "<=",
+            GTEQ => ">=",
+            AMP2 => "&&",
+            PIPE2 => "||",
+            L_CURLY_PIPE => "{|",
+            PIPE_R_CURLY => "|}",
+            ALIAS_KW => "alias",
+            AND_KW => "and",
+            AS_KW => "as",
+            ATTRIBUTE_KW => "attribute",
+            CLASS_KW => "class",
+            ELSE_KW => "else",
+            FALSE_KW => "false",
+            IF_KW => "if",
+            IMPORT_KW => "import",
+            IN_KW => "in",
+            LET_KW => "let",
+            MATCH_KW => "match",
+            MODULE_KW => "module",
+            REC_KW => "rec",
+            THEN_KW => "then",
+            TRUE_KW => "true",
+            UNION_KW => "union",
+            VALUE_KW => "value",
+            WHEN_KW => "when",
+            WHERE_KW => "where",
+            WITH_KW => "with",
+            _ => return None,
+        };
+        Some(text)
+    }
 }
 #[macro_export]
-macro_rules ! T { [;] => { $ crate :: SyntaxKind :: SEMICOLON } ; [,] => { $ crate :: SyntaxKind :: COMMA } ; ['('] => { $ crate :: SyntaxKind :: L_PAREN } ; [')'] => { $ crate :: SyntaxKind :: R_PAREN } ; ['{'] => { $ crate :: SyntaxKind :: L_CURLY } ; ['}'] => { $ crate :: SyntaxKind :: R_CURLY } ; ['['] => { $ crate :: SyntaxKind :: L_BRACKET } ; [']'] => { $ crate :: SyntaxKind :: R_BRACKET } ; [|] => { $ crate :: SyntaxKind :: PIPE } ; [*] => { $ crate :: SyntaxKind :: STAR } ; [_] => { $ crate :: SyntaxKind :: UNDERSCORE } ; [.] => { $ crate :: SyntaxKind :: DOT } ; [:] => { $ crate :: SyntaxKind :: COLON } ; [::] => { $ crate :: SyntaxKind :: COLON2 } ; [=] => { $ crate :: SyntaxKind :: EQ } ; [->] => { $ crate :: SyntaxKind :: ARROW } ; [alias] => { $ crate :: SyntaxKind :: ALIAS_KW } ; [and] => { $ crate :: SyntaxKind :: AND_KW } ; [as] => { $ crate :: SyntaxKind :: AS_KW } ; [attribute] => { $ crate :: SyntaxKind :: ATTRIBUTE_KW } ; [class] => { $ crate :: SyntaxKind :: CLASS_KW } ; [else] => { $ crate :: SyntaxKind :: ELSE_KW } ; [false] => { $ crate :: SyntaxKind :: FALSE_KW } ; [if] => { $ crate :: SyntaxKind :: IF_KW } ; [import] => { $ crate :: SyntaxKind :: IMPORT_KW } ; [in] => { $ crate :: SyntaxKind :: IN_KW } ; [let] => { $ crate :: SyntaxKind :: LET_KW } ; [match] => { $ crate :: SyntaxKind :: MATCH_KW } ; [module] => { $ crate :: SyntaxKind :: MODULE_KW } ; [rec] => { $ crate :: SyntaxKind :: REC_KW } ; [then] => { $ crate :: SyntaxKind :: THEN_KW } ; [true] => { $ crate :: SyntaxKind :: TRUE_KW } ; [union] => { $ crate :: SyntaxKind :: UNION_KW } ; [value] => { $ crate :: SyntaxKind :: VALUE_KW } ; [when] => { $ crate :: SyntaxKind :: WHEN_KW } ; [where] => { $ crate :: SyntaxKind :: WHERE_KW } ; [with] => { $ crate :: SyntaxKind :: WITH_KW } ; [ident] => { $ crate :: SyntaxKind :: IDENT } ; }
+macro_rules ! T { [;] => { $ crate :: SyntaxKind :: SEMICOLON } ; [,] => { $ crate :: SyntaxKind :: COMMA } ; ['('] => { $ crate :: SyntaxKind :: L_PAREN } ; [')'] => { $ crate :: SyntaxKind :: R_PAREN } ; ['{'] => { $ crate :: SyntaxKind :: L_CURLY } ; ['}'] => { $ crate :: SyntaxKind :: R_CURLY } ; ['['] => { $ crate :: SyntaxKind :: L_BRACKET } ; [']'] => { $ crate :: SyntaxKind :: R_BRACKET } ; [<] => { $ crate :: SyntaxKind :: L_ANGLE } ; [>] => { $ crate :: SyntaxKind :: R_ANGLE } ; [|] => { $ crate :: SyntaxKind :: PIPE } ; [+] => { $ crate :: SyntaxKind :: PLUS } ; [*] => { $ crate :: SyntaxKind :: STAR } ; [/] => { $ crate :: SyntaxKind :: SLASH } ; [%] => { $ crate :: SyntaxKind :: PERCENT } ; [_] => { $ crate :: SyntaxKind :: UNDERSCORE } ; [.] => { $ crate :: SyntaxKind :: DOT } ; [:] => { $ crate :: SyntaxKind :: COLON } ; [::] => { $ crate :: SyntaxKind :: COLON2 } ; [=] => { $ crate :: SyntaxKind :: EQ } ; [<>] => { $ crate :: SyntaxKind :: NEQ } ; [-] => { $ crate :: SyntaxKind :: MINUS } ; [->] => { $ crate :: SyntaxKind :: ARROW } ; [<=] => { $ crate :: SyntaxKind :: LTEQ } ; [>=] => { $ crate :: SyntaxKind :: GTEQ } ; [&&] => { $ crate :: SyntaxKind :: AMP2 } ; [||] => { $ crate :: SyntaxKind :: PIPE2 } ; ["{|"] => { $ crate :: SyntaxKind :: L_CURLY_PIPE } ; ["|}"] => { $ crate :: SyntaxKind :: PIPE_R_CURLY } ; [alias] => { $ crate :: SyntaxKind :: ALIAS_KW } ; [and] => { $ crate :: SyntaxKind :: AND_KW } ; [as] => { $ crate :: SyntaxKind :: AS_KW } ; [attribute] => { $ crate :: SyntaxKind :: ATTRIBUTE_KW } ; [class] => { $ crate :: SyntaxKind :: CLASS_KW } ; [else] => { $ crate :: SyntaxKind :: ELSE_KW } ; [false] => { $ crate :: SyntaxKind :: FALSE_KW } ; [if] => { $ crate :: SyntaxKind :: IF_KW } ; [import] => { $ crate :: SyntaxKind :: IMPORT_KW } ; [in] => { $ crate :: SyntaxKind :: IN_KW } ; [let] => { $ crate :: SyntaxKind :: LET_KW } ; [match] => { $ crate :: SyntaxKind :: MATCH_KW } ; [module] => { $ crate :: SyntaxKind :: MODULE_KW } ; [rec] => { $ crate :: SyntaxKind :: REC_KW } ; [then] => { $ crate :: SyntaxKind :: THEN_KW } ; [true] => { $ crate :: SyntaxKind :: TRUE_KW } ; [union] => { $ crate :: SyntaxKind :: UNION_KW } ; [value] => { $ crate :: SyntaxKind :: VALUE_KW } ; [when] => { $ crate :: SyntaxKind :: WHEN_KW } ; [where] => { $ crate :: SyntaxKind :: WHERE_KW } ; [with] => { $ crate :: SyntaxKind :: WITH_KW } ; [ident] => { $ crate :: SyntaxKind :: IDENT } ; }
@@ -0,0 +1,500 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use expect_test::expect_file;
+
+use crate::{LexedStr, PrefixEntryPoint, StrStep, TopEntryPoint};
+
+/// The files of `test_data/parser/ok` and of the snippets extracted from the
+/// grammar by `cargo codegen parser-tests` must parse without errors. Run with
+/// `UPDATE_EXPECT=1` to update the expected `.rast` trees.
+#[test]
+fn parse_ok() {
+    dir_tests(&["ok", "inline/ok"], false);
+}
+
+/// Like [`parse_ok`], but the files must have errors.
+#[test]
+fn parse_err() {
+    dir_tests(&["err", "inline/err"], true);
+}
+
+fn dir_tests(dirs: &[&str], expect_errors: bool) {
+    let test_data = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/parser");
+    let files: Vec<_> = dirs
+        .iter()
+        .flat_map(|dir| fsic_files(&test_data.join(dir)))
+        .collect();
+    assert!(!files.is_empty(), "no tests in {dirs:?}");
+
+    // The expected trees are updated even if some files are in the wrong
+    // directory, so that all of them can be reviewed at once.
+    let mut misplaced = Vec::new();
+    for path in files {
+        let text = fs::read_to_string(&path).unwrap();
+        let (actual, has_errors) = parse(TopEntryPoint::SourceFile, &text);
+        if has_errors != expect_errors {
+            misplaced.push(path.display().to_string());
+        }
+        expect_file![path.with_extension("rast")].assert_eq(&actual);
+    }
+    let problem = if expect_errors { "no errors" } else { "errors" };
+    assert!(
+        misplaced.is_empty(),
+        "{problem} in:\n{}",
+        misplaced.join("\n")
+    );
+}
+
+fn fsic_files(dir: &Path) -> Vec<PathBuf> {
+    let mut res: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("can't read {}: {err}", dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().unwrap_or_default() == "fsic")
+        .collect();
+    res.sort();
+    res
+}
+
+/// Parses `text` and renders the tree in the `KIND "text"` format, followed
+/// by the errors. Returns whether there were any errors.
+fn parse(entry: TopEntryPoint, text: &str) -> (String, bool) {
+    let lexed = LexedStr::new(text);
+    let input = lexed.to_input();
+    let output = entry.parse(&input);
+
+    let mut buf = String::new();
+    let mut errors = Vec::new();
+    let mut indent = String::new();
+    lexed.intersperse_trivia(&output, &mut |step| {
+        match step {
+            StrStep::Token { kind, text } => {
+                writeln!(buf, "{indent}{kind:?} {text:?}").unwrap();
+            },
+            StrStep::Enter { kind } => {
+                writeln!(buf, "{indent}{kind:?}").unwrap();
+                indent.push_str("  ");
+            },
+            StrStep::Exit => {
+                indent.pop();
+                indent.pop();
+            },
+            StrStep::Error { err } => {
+                let pos = lexed.input_text_range(err.range).start;
+                let mut buf = format!("error {pos}: {err}\n");
+                for label in &err.labels {
+                    let pos = lexed.input_text_range(label.range).start;
+                    writeln!(buf, "  label {pos}: {}", label.message).unwrap();
+                }
+                for note in &err.notes {
+                    writeln!(buf, "  note: {note}").unwrap();
+                }
+                errors.push(buf);
+            },
+        }
+    });
+    for (token, msg) in lexed.errors() {
+        let pos = lexed.text_start(token);
+        errors.push(format!("error {pos}: {msg}\n"));
+    }
+
+    let has_errors = !errors.is_empty();
+    for e in errors {
+        buf.push_str(&e);
+    }
+    (buf, has_errors)
+}
+
+#[track_caller]
+fn check(text: &str, expected: &str) {
+    check_at(TopEntryPoint::SourceFile, text, expected);
+}
+
+#[track_caller]
+fn check_at(entry: TopEntryPoint, text: &str, expected: &str) {
+    let (actual, _) = parse(entry, text);
+    assert_eq!(actual.trim(), expected.trim());
+}
+
+#[test]
+fn brackets_by_position() {
+    check(
+        "union Tree [a] = Node[a]",
+        r#"
+SOURCE_FILE
+  ADT_LIST
+    UNION
+      UNION_KW "union"
+      WHITESPACE " "
+      NAME
+        IDENT "Tree"
+      WHITESPACE " "
+      CONSTRAINT_LIST
+        L_BRACKET "["
+        CONSTRAINT
+          TYPE_VAR
+            IDENT "a"
+        R_BRACKET "]"
+      WHITESPACE " "
+      EQ "="
+      WHITESPACE " "
+      PATH
+        PATH_SEGMENT
+          NAME
+            IDENT "Node"
+          TYPE_ARG_LIST
+            L_BRACKET "["
+            PATH_TYPE
+              PATH
+                PATH_SEGMENT
+                  NAME
+                    IDENT "a"
+            R_BRACKET "]"
+"#,
+    );
+}
+
+#[test]
+fn brackets_by_lookahead() {
+    check(
+        "let [Eq a] f : [Ord b] b -> Option[b -> a]",
+        r#"
+SOURCE_FILE
+  LET_DECL
+    LET_KW "let"
+    WHITESPACE " "
+    CONSTRAINT_LIST
+      L_BRACKET "["
+      CONSTRAINT
+        PATH
+          PATH_SEGMENT
+            NAME
+              IDENT "Eq"
+        WHITESPACE " "
+        TYPE_VAR
+          IDENT "a"
+      R_BRACKET "]"
+    WHITESPACE " "
+    IDENT_PAT
+      NAME
+        IDENT "f"
+    WHITESPACE " "
+    COLON ":"
+    WHITESPACE " "
+    CONSTRAINT_TYPE
+      CONSTRAINT_LIST
+        L_BRACKET "["
+        CONSTRAINT
+          PATH
+            PATH_SEGMENT
+              NAME
+                IDENT "Ord"
+          WHITESPACE " "
+          TYPE_VAR
+            IDENT "b"
+        R_BRACKET "]"
+      WHITESPACE " "
+      FN_TYPE
+        PATH_TYPE
+          PATH
+            PATH_SEGMENT
+              NAME
+                IDENT "b"
+        WHITESPACE " "
+        ARROW "->"
+        WHITESPACE " "
+        PATH_TYPE
+          PATH
+            PATH_SEGMENT
+              NAME
+                IDENT "Option"
+              TYPE_ARG_LIST
+                L_BRACKET "["
+                FN_TYPE
+                  PATH_TYPE
+                    PATH
+                      PATH_SEGMENT
+                        NAME
+                          IDENT "b"
+                  WHITESPACE " "
+                  ARROW "->"
+                  WHITESPACE " "
+                  PATH_TYPE
+                    PATH
+                      PATH_SEGMENT
+                        NAME
+                          IDENT "a"
+                R_BRACKET "]"
+"#,
+    );
+}
+
+#[test]
+fn brackets_contradicting_position() {
+    let (tree, has_errors) = parse(TopEntryPoint::SourceFile, "alias A = Option[Eq a]");
+    assert!(has_errors);
+    assert!(tree.contains("CONSTRAINT_LIST"));
+    assert!(tree.contains(
+        "error 16: expected type arguments, found constraints\n  note: `[` was parsed as a \
+         constraint list"
+    ));
+
+    let (tree, has_errors) = parse(TopEntryPoint::SourceFile, "class [Option[a]] Eq a =");
+    assert!(has_errors);
+    assert!(tree.contains("TYPE_ARG_LIST"));
+    assert!(tree.contains(
+        "error 6: expected constraints, found type arguments\n  note: `[` was parsed as a type \
+         argument list"
+    ));
+
+    let (tree, has_errors) = parse(TopEntryPoint::SourceFile, "let f [Eq a] x");
+    assert!(has_errors);
+    assert!(tree.contains("CONSTRAINT_LIST"));
+    assert!(tree.contains(
+        "error 6: misplaced constraints\n  label 0: constraints must directly follow this `let`"
+    ));
+}
+
+#[test]
+fn top_entry_points() {
+    check_at(
+        TopEntryPoint::Expr,
+        "f x + 1",
+        r#"
+BIN_EXPR
+  APP_EXPR
+    PATH_EXPR
+      PATH
+        PATH_SEGMENT
+          NAME
+            IDENT "f"
+    WHITESPACE " "
+    PATH_EXPR
+      PATH
+        PATH_SEGMENT
+          NAME
+            IDENT "x"
+  WHITESPACE " "
+  OPERATOR
+    PLUS "+"
+  WHITESPACE " "
+  LITERAL
+    INT_NUMBER "1"
+"#,
+    );
+    check_at(
+        TopEntryPoint::Pattern,
+        "Some(x) :: _",
+        r#"
+CONS_PAT
+  TUPLE_VALUE_PAT
+    PATH
+      PATH_SEGMENT
+        NAME
+          IDENT "Some"
+    L_PAREN "("
+    IDENT_PAT
+      NAME
+        IDENT "x"
+    R_PAREN ")"
+  WHITESPACE " "
+  COLON2 "::"
+  WHITESPACE " "
+  WILDCARD_PAT
+    UNDERSCORE "_"
+"#,
+    );
+    check_at(
+        TopEntryPoint::Type,
+        "int -> bool )",
+        r#"
+ERROR
+  FN_TYPE
+    PATH_TYPE
+      PATH
+        PATH_SEGMENT
+          NAME
+            IDENT "int"
+    WHITESPACE " "
+    ARROW "->"
+    WHITESPACE " "
+    PATH_TYPE
+      PATH
+        PATH_SEGMENT
+          NAME
+            IDENT "bool"
+  WHITESPACE " "
+  R_PAREN ")"
+error 12: unexpected input
+"#,
+    );
+    check_at(
+        TopEntryPoint::Item,
+        "",
+        r#"
+ERROR
+error 0: expected an item, found end of input
+"#,
+    );
+}
+
+#[test]
+fn prefix_entry_points() {
+    let n_tokens = |entry: PrefixEntryPoint, text: &str| {
+        let input = LexedStr::new(text).to_input();
+        entry.parse(&input).n_input_tokens()
+    };
+    assert_eq!(n_tokens(PrefixEntryPoint::Expr, "f (x, y)) z"), 6);
+    assert_eq!(n_tokens(PrefixEntryPoint::Type, "Option[a] = None"), 4);
+    assert_eq!(n_tokens(PrefixEntryPoint::Pattern, "a, b -> a"), 3);
+    assert_eq!(
+        n_tokens(PrefixEntryPoint::Item, "import Std.List\nlet x"),
+        4
+    );
+}
+
+#[test]
+fn expected_tokens() {
+    let (tree, _) = parse(TopEntryPoint::Pattern, "Some(x y)");
+    assert!(
+        tree.contains("error 7: expected one of `::`, `|`, `as`, `)`, `,`, found an identifier")
+    );
+
+    let (tree, _) = parse(TopEntryPoint::Item, "class Eq a then");
+    assert!(tree.contains("error 11: expected one of an identifier, `=`, found `then`"));
+
+    let (tree, _) = parse(TopEntryPoint::Expr, "let x = 1");
+    assert!(tree.contains(
+        "error 9: expected an expression, found end of input\n  label 0: this `let` needs a body"
+    ));
+}
+
+#[test]
+fn recovery() {
+    check(
+        "let x\n) 92 x\nlet y",
+        r#"
+SOURCE_FILE
+  LET_DECL
+    LET_KW "let"
+    WHITESPACE " "
+    IDENT_PAT
+      NAME
+        IDENT "x"
+  NEWLINE "\n"
+  ERROR
+    R_PAREN ")"
+    WHITESPACE " "
+    INT_NUMBER "92"
+    WHITESPACE " "
+    IDENT "x"
+  NEWLINE "\n"
+  LET_DECL
+    LET_KW "let"
+    WHITESPACE " "
+    IDENT_PAT
+      NAME
+        IDENT "y"
+error 6: expected an item, found `)`
+"#,
+    );
+
+    let (tree, _) = parse(TopEntryPoint::Expr, "f (x + ) y");
+    assert!(!tree.contains("ERROR"));
+    assert!(tree.contains("error 7: expected an expression, found `)`"));
+
+    let (tree, _) = parse(TopEntryPoint::Expr, "match x with\n| ] ] -> 1\n| _ -> 0");
+    assert_eq!(tree.matches("MATCH_CASE").count(), 2);
+    assert!(tree.contains("error 15: expected a pattern, found `]`"));
+}
+
+#[test]
+fn offside() {
+    let (tree, _) = parse(
+        TopEntryPoint::SourceFile,
+        "let f x =\n    match x with\n    | A -> 1\n  | B -> 2\nlet y",
+    );
+    assert_eq!(tree.matches("MATCH_CASE").count(), 1);
+    assert!(tree.contains("ERROR\n      PIPE"));
+    assert!(tree.contains(
+        "error 42: this token is offside of the `match` started at 2:5\n  label 14: context \
+         started here"
+    ));
+
+    let (tree, _) = parse(
+        TopEntryPoint::SourceFile,
+        "let f x =\n    if a then b\n  else c\nlet y",
+    );
+    assert!(tree.contains("ERROR\n      ELSE_KW"));
+    assert!(tree.contains("error 28: this token is offside of the `if` started at 2:5"));
+
+    // An undented `else` is still taken when nothing else can take it.
+    let (tree, _) = parse(
+        TopEntryPoint::SourceFile,
+        "let f = 1 + if a then b\n          else c",
+    );
+    assert!(!tree.contains("ERROR"));
+    assert!(tree.contains("error 34: this token is offside of the `if` started at 1:13"));
+
+    let (_, has_errors) = parse(
+        TopEntryPoint::SourceFile,
+        "let xs = (\n    1,\n    2\n)\nlet y",
+    );
+    assert!(!has_errors);
+
+    let (tree, _) = parse(TopEntryPoint::SourceFile, "import A\n  B\nlet y");
+    assert!(tree.contains("error 11: expected a line at column 1, found an identifier"));
+
+    let (tree, _) = parse(
+        TopEntryPoint::SourceFile,
+        "module N =\n    let x\n  let y\nlet z",
+    );
+    assert!(tree.contains("error 23: this token is offside of the `let` started at 2:5"));
+}
+
+#[test]
+fn attached_comments() {
+    check(
+        "//! File docs.\n\n// Not attached.\n\n/// A.\n\n/** B. */\nunion U = A\n/// Imported.\nimport Std",
+        r#"
+SOURCE_FILE
+  COMMENT "//! File docs."
+  NEWLINE "\n"
+  NEWLINE "\n"
+  COMMENT "// Not attached."
+  NEWLINE "\n"
+  NEWLINE "\n"
+  ADT_LIST
+    COMMENT "/// A."
+    NEWLINE "\n"
+    NEWLINE "\n"
+    COMMENT "/** B. */"
+    NEWLINE "\n"
+    UNION
+      UNION_KW "union"
+      WHITESPACE " "
+      NAME
+        IDENT "U"
+      WHITESPACE " "
+      EQ "="
+      WHITESPACE " "
+      PATH
+        PATH_SEGMENT
+          NAME
+            IDENT "A"
+  NEWLINE "\n"
+  IMPORT
+    COMMENT "/// Imported."
+    NEWLINE "\n"
+    IMPORT_KW "import"
+    WHITESPACE " "
+    PATH
+      PATH_SEGMENT
+        NAME
+          IDENT "Std"
+"#,
+    );
+}
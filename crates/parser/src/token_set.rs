@@ -0,0 +1,40 @@
+//! A bit-set of `SyntaxKind`s.
+
+use crate::SyntaxKind;
+
+/// A bit-set of token `SyntaxKind`s, cheap to copy and to build in `const`
+/// context.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TokenSet(u128);
+
+// Only tokens go into a `TokenSet`, and tokens come before nodes in
+// `SyntaxKind`.
+const _: () = assert!((SyntaxKind::WHITESPACE as usize) < 128);
+
+impl TokenSet {
+    pub(crate) const EMPTY: TokenSet = TokenSet(0);
+
+    pub(crate) const fn new(kinds: &[SyntaxKind]) -> TokenSet {
+        let mut res = 0u128;
+        let mut i = 0;
+        while i < kinds.len() {
+            res |= mask(kinds[i]);
+            i += 1;
+        }
+        TokenSet(res)
+    }
+
+    pub(crate) const fn union(self, other: TokenSet) -> TokenSet {
+        TokenSet(self.0 | other.0)
+    }
+
+    pub(crate) const fn contains(&self, kind: SyntaxKind) -> bool {
+        self.0 & mask(kind) != 0
+    }
+}
+
+const fn mask(kind: SyntaxKind) -> u128 {
+    let discriminant = kind as usize;
+    assert!(discriminant < 128, "only tokens can be in a `TokenSet`");
+    1u128 << discriminant
+}
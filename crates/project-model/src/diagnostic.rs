@@ -0,0 +1,78 @@
+use std::{fmt, path::PathBuf};
+
+use syntax::TextRange;
+
+use crate::{ModulePath, ModuleSource};
+
+/// A problem with the layout of the modules of a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Two modules have the same path. The first one is kept.
+    DuplicateModule {
+        path: ModulePath,
+        first: ModuleSource,
+        second: ModuleSource,
+    },
+    /// The preamble of a file names another module than its location does.
+    /// The preamble is kept.
+    PreambleMismatch {
+        file: PathBuf,
+        /// The range of the path of the preamble.
+        range: TextRange,
+        preamble: ModulePath,
+        location: ModulePath,
+    },
+    /// The name of a file, or of a directory it is in, has a `.`, as in
+    /// `Std/List.Extra.fsic`, so its location is not a module path. The file is
+    /// the module of its preamble, and left out without one.
+    DottedFileName { file: PathBuf },
+    /// The parent of a module does not exist, so the module cannot be
+    /// reached from the root. The modules inside it are not reported.
+    UnreachableModule {
+        path: ModulePath,
+        source: ModuleSource,
+    },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::DuplicateModule {
+                path,
+                first,
+                second,
+            } => {
+                write!(
+                    f,
+                    "module `{path}` is defined twice, in {first} and in {second}"
+                )
+            },
+            Diagnostic::PreambleMismatch {
+                file,
+                range: _,
+                preamble,
+                location,
+            } => {
+                write!(
+                    f,
+                    "`{}` declares module `{preamble}`, but its location makes it `{location}`",
+                    file.display()
+                )
+            },
+            Diagnostic::DottedFileName { file } => {
+                write!(
+                    f,
+                    "the location of `{}` is not a module path, as a name in it contains a `.`",
+                    file.display()
+                )
+            },
+            Diagnostic::UnreachableModule { path, source } => {
+                let parent = path.parent().unwrap_or_default();
+                write!(
+                    f,
+                    "module `{path}` in {source} cannot be reached, as there is no module `{parent}`"
+                )
+            },
+        }
+    }
+}
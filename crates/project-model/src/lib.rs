@@ -0,0 +1,334 @@
+//! The model of a project: the tree of the modules in its source directory,
+//! and where they are defined.
+//!
+//! A file is the module that its preamble names, as in `module Std.List`.
+//! Without a preamble, it is the module of its location: `Std/List.fsic` is
+//! `Std.List`. Directories with such files are modules too, and the
+//! `module X =` items of a file are modules inside the module of the file.
+
+mod diagnostic;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fmt, fs, io,
+    ops::Index,
+    path::{Path, PathBuf},
+};
+
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+use syntax::{
+    AstNode, SourceFile, TextRange,
+    ast::{self, HasName},
+};
+
+pub use crate::diagnostic::Diagnostic;
+
+/// The extension of source files.
+pub const EXTENSION: &str = "fsic";
+
+/// The path of a module from the root, as `Std.List`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModulePath(Vec<SmolStr>);
+
+impl ModulePath {
+    /// The path of the root module, which has no segments.
+    pub fn root() -> ModulePath {
+        ModulePath::default()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn segments(&self) -> &[SmolStr] {
+        &self.0
+    }
+
+    /// The last segment, or `None` for the root.
+    pub fn name(&self) -> Option<&SmolStr> {
+        self.0.last()
+    }
+
+    /// The path without the last segment, or `None` for the root.
+    pub fn parent(&self) -> Option<ModulePath> {
+        let (_, parent) = self.0.split_last()?;
+        Some(ModulePath(parent.to_vec()))
+    }
+
+    pub fn join(&self, segment: impl Into<SmolStr>) -> ModulePath {
+        let mut res = self.clone();
+        res.0.push(segment.into());
+        res
+    }
+}
+
+impl<S: Into<SmolStr>> FromIterator<S> for ModulePath {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        ModulePath(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl fmt::Display for ModulePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return f.write_str("<root>");
+        }
+        f.write_str(&self.0.join("."))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModuleId(u32);
+
+/// Where a module is defined. Paths are relative to the source directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleSource {
+    /// A directory that no file is the module of, as the root.
+    Directory(PathBuf),
+    File(PathBuf),
+    /// A `module X =` item, with the range of its name.
+    Inline {
+        file: PathBuf,
+        range: TextRange,
+    },
+}
+
+impl fmt::Display for ModuleSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleSource::Directory(dir) if dir.as_os_str().is_empty() => {
+                f.write_str("the source directory")
+            },
+            ModuleSource::Directory(dir) => write!(f, "directory `{}`", dir.display()),
+            ModuleSource::File(file) => write!(f, "`{}`", file.display()),
+            ModuleSource::Inline { file, range } => write!(f, "`{}` at {range:?}", file.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleData {
+    pub path: ModulePath,
+    pub source: ModuleSource,
+    /// `None` for the root, and for the modules that cannot be reached.
+    pub parent: Option<ModuleId>,
+    pub children: Vec<ModuleId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectModel {
+    modules: Vec<ModuleData>,
+    by_path: FxHashMap<ModulePath, ModuleId>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl ProjectModel {
+    /// Reads the source files in `dir` and its subdirectories, skipping the
+    /// hidden ones.
+    pub fn discover(dir: &Path) -> io::Result<ProjectModel> {
+        let mut files = Vec::new();
+        collect_files(dir, Path::new(""), &mut files)?;
+        Ok(ProjectModel::from_files(files))
+    }
+
+    /// Builds the model of the files with the given texts, by their paths
+    /// relative to the source directory.
+    pub fn from_files(files: impl IntoIterator<Item = (PathBuf, String)>) -> ProjectModel {
+        let mut files: Vec<_> = files.into_iter().collect();
+        files.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut res = ProjectModel {
+            modules: Vec::new(),
+            by_path: FxHashMap::default(),
+            diagnostics: Vec::new(),
+        };
+        let root = ModuleSource::Directory(PathBuf::new());
+        res.define(ModulePath::root(), root, None);
+
+        let mut defined = Vec::new();
+        let mut located = Vec::new();
+        for (file, text) in &files {
+            let location = components_path(&file.with_extension(""));
+            let is_dotted = location.segments().iter().any(|it| it.contains('.'));
+            if is_dotted {
+                res.diagnostics
+                    .push(Diagnostic::DottedFileName { file: file.clone() });
+            }
+            let tree = SourceFile::parse(text).tree();
+            let (path, is_located) = match preamble_path(&tree) {
+                Some((preamble, range)) => {
+                    let is_located = !is_dotted && preamble == location;
+                    if !is_dotted && !is_located {
+                        res.diagnostics.push(Diagnostic::PreambleMismatch {
+                            file: file.clone(),
+                            range,
+                            preamble: preamble.clone(),
+                            location,
+                        });
+                    }
+                    (preamble, is_located)
+                },
+                None if is_dotted => continue,
+                None => (location, true),
+            };
+            if is_located {
+                located.push(file);
+            }
+            if let Some(module) = res.define(path, ModuleSource::File(file.clone()), None) {
+                defined.push((module, file, tree));
+            }
+        }
+        // Only the files that are the modules of their locations make the
+        // directories they are in modules.
+        for file in located {
+            for dir in file.ancestors().skip(1) {
+                let path = components_path(dir);
+                if !path.is_root() && !res.by_path.contains_key(&path) {
+                    res.define(path, ModuleSource::Directory(dir.to_owned()), None);
+                }
+            }
+        }
+        for (module, file, tree) in defined {
+            res.define_inline(module, file, tree.items());
+        }
+        res.link();
+        res
+    }
+
+    pub fn root(&self) -> ModuleId {
+        ModuleId(0)
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = (ModuleId, &ModuleData)> {
+        (0..).map(ModuleId).zip(&self.modules)
+    }
+
+    pub fn module_by_path(&self, path: &ModulePath) -> Option<ModuleId> {
+        self.by_path.get(path).copied()
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Adds the module at `path`, unless there already is one.
+    fn define(
+        &mut self,
+        path: ModulePath,
+        source: ModuleSource,
+        parent: Option<ModuleId>,
+    ) -> Option<ModuleId> {
+        if let Some(&first) = self.by_path.get(&path) {
+            self.diagnostics.push(Diagnostic::DuplicateModule {
+                path,
+                first: self[first].source.clone(),
+                second: source,
+            });
+            return None;
+        }
+        let id = ModuleId(self.modules.len() as u32);
+        self.by_path.insert(path.clone(), id);
+        self.modules.push(ModuleData {
+            path,
+            source,
+            parent,
+            children: Vec::new(),
+        });
+        if let Some(parent) = parent {
+            self.modules[parent.0 as usize].children.push(id);
+        }
+        Some(id)
+    }
+
+    fn define_inline(&mut self, parent: ModuleId, file: &Path, items: ast::AstChildren<ast::Item>) {
+        for item in items {
+            let ast::Item::Module(module) = item else {
+                continue;
+            };
+            let Some(name) = module.name() else {
+                continue;
+            };
+            let Some(ident) = name.ident_token() else {
+                continue;
+            };
+            let path = self[parent].path.join(ident.text());
+            let source = ModuleSource::Inline {
+                file: file.to_owned(),
+                range: name.syntax().text_range(),
+            };
+            if let Some(id) = self.define(path, source, Some(parent)) {
+                self.define_inline(id, file, module.items());
+            }
+        }
+    }
+
+    /// Links the modules of files and directories to their parents.
+    fn link(&mut self) {
+        for i in 1..self.modules.len() {
+            let module = &self.modules[i];
+            if module.parent.is_some() {
+                continue;
+            }
+            let parent_path = module.path.parent().unwrap_or_default();
+            match self.by_path.get(&parent_path) {
+                Some(&parent) => {
+                    self.modules[i].parent = Some(parent);
+                    self.modules[parent.0 as usize]
+                        .children
+                        .push(ModuleId(i as u32));
+                },
+                None => {
+                    self.diagnostics.push(Diagnostic::UnreachableModule {
+                        path: module.path.clone(),
+                        source: module.source.clone(),
+                    })
+                },
+            }
+        }
+    }
+}
+
+impl Index<ModuleId> for ProjectModel {
+    type Output = ModuleData;
+
+    fn index(&self, id: ModuleId) -> &ModuleData {
+        &self.modules[id.0 as usize]
+    }
+}
+
+/// The module of a directory, or of a file without its extension, by its
+/// location.
+fn components_path(path: &Path) -> ModulePath {
+    path.components()
+        .map(|it| SmolStr::new(it.as_os_str().to_string_lossy()))
+        .collect()
+}
+
+fn preamble_path(file: &SourceFile) -> Option<(ModulePath, TextRange)> {
+    let path = file.preamble()?.path()?;
+    let segments = path
+        .segments()
+        .map(|it| Some(SmolStr::new(it.name()?.ident_token()?.text())))
+        .collect::<Option<ModulePath>>()?;
+    Some((segments, path.syntax().text_range()))
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<(PathBuf, String)>) -> io::Result<()> {
+    let mut entries = fs::read_dir(root.join(dir))?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|it| it.file_name());
+    for entry in entries {
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = dir.join(&name);
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if path.extension().is_some_and(|it| it == EXTENSION) {
+            let text = fs::read_to_string(root.join(&path))?;
+            files.push((path, text));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use std::{fmt::Write, fs};
+
+use expect_test::{Expect, expect};
+
+use super::*;
+
+/// Checks the tree of modules of `files` and the diagnostics.
+#[track_caller]
+fn check(files: &[(&str, &str)], expect: Expect) {
+    let files = files
+        .iter()
+        .map(|(path, text)| (PathBuf::from(path), text.to_string()));
+    expect.assert_eq(&dump(&ProjectModel::from_files(files)));
+}
+
+fn dump(model: &ProjectModel) -> String {
+    fn go(model: &ProjectModel, module: ModuleId, depth: usize, buf: &mut String) {
+        let data = &model[module];
+        let name = data.path.name().map_or("<root>", |it| it.as_str());
+        writeln!(
+            buf,
+            "{:indent$}{name}: {}",
+            "",
+            data.source,
+            indent = depth * 2
+        )
+        .unwrap();
+        let mut children = data.children.clone();
+        children.sort_by_key(|&it| model[it].path.clone());
+        for child in children {
+            go(model, child, depth + 1, buf);
+        }
+    }
+
+    let mut buf = String::new();
+    go(model, model.root(), 0, &mut buf);
+    for diagnostic in model.diagnostics() {
+        writeln!(buf, "error: {diagnostic}").unwrap();
+    }
+    buf
+}
+
+#[test]
+fn modules_of_locations() {
+    check(
+        &[
+            ("Main.fsic", "import Std.List\nlet main = 0"),
+            ("Std/List.fsic", "module Std.List\n\nlet empty = []"),
+            (
+                "Std/Option.fsic",
+                "module Inner =\n    module Deeper =\n        let x = 1\nmodule Other =\n    let y = 2",
+            ),
+            ("Std/Text/Format.fsic", "let format = 0"),
+        ],
+        expect![[r#"
+            <root>: the source directory
+              Main: `Main.fsic`
+              Std: directory `Std`
+                List: `Std/List.fsic`
+                Option: `Std/Option.fsic`
+                  Inner: `Std/Option.fsic` at 7..12
+                    Deeper: `Std/Option.fsic` at 26..32
+                  Other: `Std/Option.fsic` at 60..65
+                Text: directory `Std/Text`
+                  Format: `Std/Text/Format.fsic`
+        "#]],
+    );
+}
+
+#[test]
+fn file_of_directory() {
+    // The file and the directory are the same module.
+    check(
+        &[
+            ("Std.fsic", "let version = 1"),
+            ("Std/List.fsic", "let empty = []"),
+        ],
+        expect![[r#"
+            <root>: the source directory
+              Std: `Std.fsic`
+                List: `Std/List.fsic`
+        "#]],
+    );
+}
+
+#[test]
+fn preamble_mismatch() {
+    check(
+        &[
+            ("Util.fsic", "module Helpers\nlet id x = x"),
+            ("Std/List.fsic", "module Std.Lists"),
+        ],
+        expect![[r#"
+            <root>: the source directory
+              Helpers: `Util.fsic`
+            error: `Std/List.fsic` declares module `Std.Lists`, but its location makes it `Std.List`
+            error: `Util.fsic` declares module `Helpers`, but its location makes it `Util`
+            error: module `Std.Lists` in `Std/List.fsic` cannot be reached, as there is no module `Std`
+        "#]],
+    );
+}
+
+#[test]
+fn directories_of_renamed_files() {
+    // `Lib` only holds a file that is another module, so it is not a module.
+    // `Std` is one through `Std/Option.fsic`.
+    check(
+        &[
+            ("Lib/Util.fsic", "module Helpers"),
+            ("Std/List.fsic", "module Std.Lists"),
+            ("Std/Option.fsic", "let none = 0"),
+        ],
+        expect![[r#"
+            <root>: the source directory
+              Helpers: `Lib/Util.fsic`
+              Std: directory `Std`
+                Lists: `Std/List.fsic`
+                Option: `Std/Option.fsic`
+            error: `Lib/Util.fsic` declares module `Helpers`, but its location makes it `Lib.Util`
+            error: `Std/List.fsic` declares module `Std.Lists`, but its location makes it `Std.List`
+        "#]],
+    );
+}
+
+#[test]
+fn dotted_file_names() {
+    check(
+        &[
+            ("List.Extra.fsic", "let extra = 0"),
+            ("Option.Extra.fsic", "module OptionExtra"),
+            ("Std.Old/List.fsic", "let empty = []"),
+        ],
+        expect![[r#"
+            <root>: the source directory
+              OptionExtra: `Option.Extra.fsic`
+            error: the location of `List.Extra.fsic` is not a module path, as a name in it contains a `.`
+            error: the location of `Option.Extra.fsic` is not a module path, as a name in it contains a `.`
+            error: the location of `Std.Old/List.fsic` is not a module path, as a name in it contains a `.`
+        "#]],
+    );
+}
+
+#[test]
+fn duplicate_modules() {
+    check(
+        &[
+            (
+                "A.fsic",
+                "module M =\n    let x = 1\nmodule M =\n    let y = 2",
+            ),
+            ("B.fsic", "module A"),
+        ],
+        expect![[r#"
+            <root>: the source directory
+              A: `A.fsic`
+                M: `A.fsic` at 7..8
+            error: `B.fsic` declares module `A`, but its location makes it `B`
+            error: module `A` is defined twice, in `A.fsic` and in `B.fsic`
+            error: module `A.M` is defined twice, in `A.fsic` at 7..8 and in `A.fsic` at 32..33
+        "#]],
+    );
+}
+
+#[test]
+fn unreachable_modules() {
+    // Nothing defines `Legacy`, so `Legacy.Old` and what is inside it are
+    // left out of the tree.
+    check(
+        &[
+            (
+                "Old.fsic",
+                "module Legacy.Old\nmodule Inner =\n    let x = 1",
+            ),
+            ("Older.fsic", "module Legacy.Old.Older"),
+        ],
+        expect![[r#"
+            <root>: the source directory
+            error: `Old.fsic` declares module `Legacy.Old`, but its location makes it `Old`
+            error: `Older.fsic` declares module `Legacy.Old.Older`, but its location makes it `Older`
+            error: module `Legacy.Old` in `Old.fsic` cannot be reached, as there is no module `Legacy`
+        "#]],
+    );
+}
+
+#[test]
+fn discover() {
+    let dir = std::env::temp_dir().join(format!("fsicc-discover-{}", std::process::id()));
+    let write = |path: &str, text: &str| {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    };
+    write("Main.fsic", "let main = 0");
+    write("Std/List.fsic", "module Std.List");
+    write("Std/README.md", "# Std");
+    write(".cache/Stale.fsic", "let stale = 0");
+
+    let model = ProjectModel::discover(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+    let actual = dump(&model.unwrap());
+    expect![[r#"
+        <root>: the source directory
+          Main: `Main.fsic`
+          Std: directory `Std`
+            List: `Std/List.fsic`
+    "#]]
+    .assert_eq(&actual);
+}
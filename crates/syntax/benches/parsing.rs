@@ -0,0 +1,79 @@
+//! Throughput of the parsing pipeline on a large generated module, stage by
+//! stage: lexing, parsing into events, and building the syntax tree.
+//!
+//! The generated module has 100k lines, about 2 MB. On a release build the
+//! throughput targets are at least 80 MB/s for lexing, 20 MB/s for parsing
+//! into events, including the parser's input, and 5 MB/s for the whole
+//! pipeline up to the tree, including freeing it.
+//!
+//! Run with `cargo bench -p fsicc-syntax`.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use fsicc_syntax::SourceFile;
+use parser::{LexedStr, TopEntryPoint};
+
+/// The number of lines in the generated module.
+const LINES: usize = 100_000;
+
+/// A chunk of the generated module, `{i}` is replaced with its index.
+const CHUNK: &str = "
+/// Compares the items of a list.
+class [Eq a] Ord{i} a =
+    let compare : a -> a -> int
+
+where IntOrd{i} Ord{i}[int] =
+    let compare x y = x - y
+
+union Option{i} [a] = None | Some[a]
+
+module Nested{i} =
+    let x = {i}
+    let y = x + 1 // the next one
+
+let rec length{i} xs =
+    match xs with
+    | Nil -> 0
+    | _ :: rest -> 1 + length{i} rest
+
+let main{i} =
+    let xs = 1 :: 2 :: Nil
+    let n = length{i} xs
+    if n > 1 && n <> 3
+    then print \"two\"
+    else
+        print f\"not two: {n}\"
+        print (n : int, 'x', 1.5)
+";
+
+fn module() -> String {
+    let chunk_lines = CHUNK.lines().count();
+    (0..LINES / chunk_lines)
+        .map(|i| CHUNK.replace("{i}", &i.to_string()))
+        .collect()
+}
+
+fn parsing(c: &mut Criterion) {
+    let text = module();
+    let mut group = c.benchmark_group("parsing");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.sample_size(20);
+
+    group.bench_function("lex", |b| b.iter(|| LexedStr::new(black_box(&text))));
+
+    let lexed = LexedStr::new(&text);
+    group.bench_function("parse", |b| {
+        b.iter(|| {
+            let input = lexed.to_input();
+            TopEntryPoint::SourceFile.parse(black_box(&input))
+        })
+    });
+
+    group.bench_function("tree", |b| b.iter(|| SourceFile::parse(black_box(&text))));
+
+    group.finish();
+}
+
+criterion_group!(benches, parsing);
+criterion_main!(benches);
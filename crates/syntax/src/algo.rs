@@ -0,0 +1,244 @@
+//! Algorithms over whole syntax trees.
+
+use crate::{
+    NodeOrToken, SyntaxElement, SyntaxNode, TextEdit, TextEditBuilder, TextRange, TextSize,
+};
+
+/// Above this many pairs of children, the changed children of a node are
+/// replaced as a whole rather than searched for the unchanged ones among
+/// them.
+const MAX_LCS_PAIRS: usize = 1 << 16;
+
+/// The edit that turns the text of `old` into the text of `new`.
+///
+/// The trees are walked together, and the subtrees that are the same on both
+/// sides are left alone: only what changed is replaced, as in an identifier
+/// rather than the item around it.
+pub fn diff(old: &SyntaxNode, new: &SyntaxNode) -> TextEdit {
+    let mut builder = TextEdit::builder();
+    diff_node(old, new, &mut builder);
+    builder.finish()
+}
+
+fn diff_node(old: &SyntaxNode, new: &SyntaxNode, builder: &mut TextEditBuilder) {
+    if old.kind() != new.kind() {
+        let (old_text, new_text) = (old.to_string(), new.to_string());
+        replace(builder, old.text_range(), &old_text, &new_text);
+        return;
+    }
+    if old.green() == new.green() {
+        return;
+    }
+
+    let old_children: Vec<SyntaxElement> = old.children_with_tokens().collect();
+    let new_children: Vec<SyntaxElement> = new.children_with_tokens().collect();
+    let (mut i, mut j) = (0, 0);
+    let mut at = old.text_range().start();
+    let end = (old_children.len(), new_children.len());
+    for (anchor_i, anchor_j) in unchanged(&old_children, &new_children).chain([end]) {
+        diff_children(
+            &old_children[i..anchor_i],
+            &new_children[j..anchor_j],
+            at,
+            builder,
+        );
+        if let Some(anchor) = old_children.get(anchor_i) {
+            at = anchor.text_range().end();
+        }
+        (i, j) = (anchor_i + 1, anchor_j + 1);
+    }
+}
+
+/// Diffs the children of a node that changed, starting at `at` in the old
+/// tree.
+fn diff_children(
+    old: &[SyntaxElement],
+    new: &[SyntaxElement],
+    at: TextSize,
+    builder: &mut TextEditBuilder,
+) {
+    let same_kinds = old.len() == new.len()
+        && old
+            .iter()
+            .zip(new)
+            .all(|(old, new)| old.kind() == new.kind());
+    if same_kinds {
+        for (old, new) in old.iter().zip(new) {
+            match (old, new) {
+                (NodeOrToken::Node(old), NodeOrToken::Node(new)) => diff_node(old, new, builder),
+                (NodeOrToken::Token(old), NodeOrToken::Token(new)) => {
+                    replace(builder, old.text_range(), old.text(), new.text())
+                },
+                _ => unreachable!("nodes and tokens have different kinds"),
+            }
+        }
+        return;
+    }
+    let range = TextRange::new(at, old.last().map_or(at, |it| it.text_range().end()));
+    let old_text: String = old.iter().map(|it| it.to_string()).collect();
+    let new_text: String = new.iter().map(|it| it.to_string()).collect();
+    replace(builder, range, &old_text, &new_text);
+}
+
+/// The pairs of indices of the children that are the same in `old` and
+/// `new`, in order, keeping as much of the text as possible.
+fn unchanged(old: &[SyntaxElement], new: &[SyntaxElement]) -> impl Iterator<Item = (usize, usize)> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| same(old, new))
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| same(old, new))
+        .count();
+    let (old_mid, new_mid) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    let mid = match old_mid.len() * new_mid.len() <= MAX_LCS_PAIRS {
+        true => lcs(old_mid, new_mid),
+        false => Vec::new(),
+    };
+    let (old_len, new_len) = (old.len(), new.len());
+    (0..prefix)
+        .map(|it| (it, it))
+        .chain(mid.into_iter().map(move |(i, j)| (prefix + i, prefix + j)))
+        .chain(
+            (1..=suffix)
+                .rev()
+                .map(move |it| (old_len - it, new_len - it)),
+        )
+}
+
+/// The common subsequence of `old` and `new` with the longest text, as pairs
+/// of indices. Weighing by text, an item is kept rather than the newline
+/// after it.
+fn lcs(old: &[SyntaxElement], new: &[SyntaxElement]) -> Vec<(usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+    // `lens[i][j]` is the text length of the LCS of `old[i..]` and `new[j..]`.
+    let mut lens = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lens[i][j] = match same(&old[i], &new[j]) {
+                true => lens[i + 1][j + 1] + u32::from(old[i].text_range().len()),
+                false => lens[i + 1][j].max(lens[i][j + 1]),
+            };
+        }
+    }
+    let mut res = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if same(&old[i], &new[j])
+            && lens[i][j] == lens[i + 1][j + 1] + u32::from(old[i].text_range().len())
+        {
+            res.push((i, j));
+            (i, j) = (i + 1, j + 1);
+        } else if lens[i + 1][j] >= lens[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    res
+}
+
+fn same(old: &SyntaxElement, new: &SyntaxElement) -> bool {
+    match (old, new) {
+        (NodeOrToken::Node(old), NodeOrToken::Node(new)) => old.green() == new.green(),
+        (NodeOrToken::Token(old), NodeOrToken::Token(new)) => {
+            old.kind() == new.kind() && old.text() == new.text()
+        },
+        _ => false,
+    }
+}
+
+/// Replaces `range`, where the text is `old`, with `new`, leaving out what
+/// they start and end with in common.
+fn replace(builder: &mut TextEditBuilder, range: TextRange, old: &str, new: &str) {
+    let prefix = common_len(old.char_indices(), new.char_indices());
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = common_len(old.char_indices().rev(), new.char_indices().rev());
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+    let start = range.start() + TextSize::try_from(prefix).unwrap();
+    builder.replace(TextRange::at(start, TextSize::of(old)), new.to_owned());
+}
+
+/// The length in bytes of the chars that `old` and `new` have in common from
+/// their start.
+fn common_len(
+    old: impl Iterator<Item = (usize, char)>,
+    new: impl Iterator<Item = (usize, char)>,
+) -> usize {
+    old.zip(new)
+        .take_while(|((_, old), (_, new))| old == new)
+        .map(|((_, c), _)| c.len_utf8())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceFile;
+
+    /// Checks that the diff of the files `old` and `new` turns one into the
+    /// other with the `expected` edits.
+    #[track_caller]
+    fn check(old: &str, new: &str, expected: &[(u32, u32, &str)]) {
+        let old_file = SourceFile::parse(old).syntax_node();
+        let new_file = SourceFile::parse(new).syntax_node();
+        let edit = diff(&old_file, &new_file);
+        let mut text = old.to_owned();
+        edit.apply(&mut text);
+        assert_eq!(text, new);
+        let actual: Vec<(u32, u32, &str)> = edit
+            .iter()
+            .map(|it| {
+                (
+                    it.delete.start().into(),
+                    it.delete.end().into(),
+                    it.insert.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn diff_unchanged() {
+        check("let x = 1\nlet y = 2", "let x = 1\nlet y = 2", &[]);
+    }
+
+    #[test]
+    fn diff_tokens() {
+        // Renaming `foo` to `fob` only touches the last letter.
+        check(
+            "let foo = 1\nlet bar = foo + 2",
+            "let fob = 1\nlet bar = fob + 20",
+            &[(6, 7, "b"), (24, 25, "b"), (29, 29, "0")],
+        );
+    }
+
+    #[test]
+    fn diff_items() {
+        // A new item in the middle, and one removed from the end.
+        check(
+            "let a = 1\nlet c = 3\nlet d = 4\n",
+            "let a = 1\nlet b = 2\nlet c = 3\n",
+            &[(10, 10, "let b = 2\n"), (19, 29, "")],
+        );
+        // A different kind of node.
+        check("let f x =\n    x + 1", "let f x =\n    g x", &[(
+            14, 19, "g x",
+        )]);
+    }
+
+    #[test]
+    fn diff_unicode() {
+        check("let s = \"привет\"", "let s = \"прибой\"", &[(
+            15, 21, "бой",
+        )]);
+    }
+}
@@ -0,0 +1,253 @@
+//! The documentation of items, written as Markdown in doc comments.
+
+use std::fmt;
+
+use crate::{
+    Direction, SyntaxElement,
+    SyntaxKind::ADT_LIST,
+    SyntaxNode, T, TextRange, TextSize,
+    ast::{self, AstNode, AstToken, DocPlacement},
+};
+
+/// The Markdown text of the doc comments of an item, with the places in the
+/// source that its lines come from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Documentation {
+    text: String,
+    /// Where each line starts in `text`, and its range in the source.
+    lines: Vec<(TextSize, TextRange)>,
+}
+
+impl Documentation {
+    /// Joins the lines of `comments` into Markdown, without the comment
+    /// markers, the leading `*`s of block comments and the indentation that
+    /// all lines have. Returns `None` if there are no comments.
+    pub fn from_comments(
+        comments: impl IntoIterator<Item = ast::Comment>,
+    ) -> Option<Documentation> {
+        // The lines, and where they start in the source.
+        let mut lines: Vec<(String, TextSize)> = Vec::new();
+        for comment in comments {
+            comment_lines(&comment, &mut lines);
+        }
+        if lines.is_empty() {
+            return None;
+        }
+
+        let indent = lines
+            .iter()
+            .filter(|(line, _)| !line.is_empty())
+            .map(|(line, _)| line.len() - line.trim_start_matches(' ').len())
+            .min()
+            .unwrap_or(0);
+        let mut res = Documentation {
+            text: String::new(),
+            lines: Vec::with_capacity(lines.len()),
+        };
+        for (i, (line, start)) in lines.iter().enumerate() {
+            if i > 0 {
+                res.text.push('\n');
+            }
+            // Blank lines are shorter than the indentation.
+            let cut = indent.min(line.len());
+            let line = &line[cut..];
+            let source = TextRange::at(*start + offset(cut), TextSize::of(line));
+            res.lines.push((TextSize::of(&res.text), source));
+            res.text.push_str(line);
+        }
+        Some(res)
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The ranges in the source of the lines of the text, in order.
+    pub fn source_ranges(&self) -> impl Iterator<Item = TextRange> + '_ {
+        self.lines.iter().map(|&(_, source)| source)
+    }
+
+    /// Maps `range` of the text to the source. Returns `None` if `range` is
+    /// not within one line, as the lines are apart in the source.
+    pub fn source_range(&self, range: TextRange) -> Option<TextRange> {
+        let line = self
+            .lines
+            .partition_point(|&(start, _)| start <= range.start())
+            .checked_sub(1)?;
+        let (start, source) = self.lines[line];
+        let res = TextRange::at(source.start() + (range.start() - start), range.len());
+        source.contains_range(res).then_some(res)
+    }
+}
+
+impl fmt::Display for Documentation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// The outer doc comments attached to `node`, then its inner doc comments.
+pub(crate) fn doc_comments(node: &SyntaxNode) -> Vec<ast::Comment> {
+    let mut res = Vec::new();
+    if let Some(list) = node.parent().filter(|it| it.kind() == ADT_LIST) {
+        if list.first_child().as_ref() == Some(node) {
+            // The parser attaches the comments to the list of the first type.
+            res.extend(leading_trivia(&list));
+        } else {
+            // The docs of the following types go before `and`.
+            let and = node
+                .siblings_with_tokens(Direction::Prev)
+                .skip(1)
+                .find(|it| !it.kind().is_trivia())
+                .and_then(|it| it.into_token())
+                .filter(|it| it.kind() == T![and]);
+            let before_and = and.into_iter().flat_map(|it| {
+                it.siblings_with_tokens(Direction::Prev)
+                    .skip(1)
+                    .take_while(|it| it.kind().is_trivia())
+            });
+            let mut comments: Vec<_> = before_and.filter_map(into_comment).collect();
+            comments.reverse();
+            res.extend(comments);
+        }
+    }
+    res.extend(leading_trivia(node));
+    res.retain(|it| it.doc_placement() == Some(DocPlacement::Outer));
+    res.extend(
+        node.children_with_tokens()
+            .filter_map(into_comment)
+            .filter(|it| it.doc_placement() == Some(DocPlacement::Inner)),
+    );
+    res
+}
+
+fn leading_trivia(node: &SyntaxNode) -> impl Iterator<Item = ast::Comment> {
+    node.children_with_tokens()
+        .take_while(|it| it.kind().is_trivia())
+        .filter_map(into_comment)
+}
+
+fn into_comment(element: SyntaxElement) -> Option<ast::Comment> {
+    ast::Comment::cast(element.into_token()?)
+}
+
+/// Pushes the lines of `comment`, without the comment markers, to `lines`.
+fn comment_lines(comment: &ast::Comment, lines: &mut Vec<(String, TextSize)>) {
+    let start = comment.syntax().text_range().start();
+    let text = comment.text();
+    if !comment.is_block() {
+        lines.push((text[3..].trim_end().to_owned(), start + offset(3)));
+        return;
+    }
+
+    // Whether the line comes after a line break, with its text and offset.
+    let mut block: Vec<(bool, &str, usize)> = Vec::new();
+    let mut at = 3;
+    for (i, line) in text[3..text.len() - 2].split('\n').enumerate() {
+        block.push((i > 0, line.trim_end(), at));
+        at += line.len() + 1;
+    }
+    if block.first().is_some_and(|(_, line, _)| line.is_empty()) {
+        block.remove(0);
+    }
+    if block
+        .last()
+        .is_some_and(|(_, line, _)| line.trim().is_empty())
+    {
+        block.pop();
+    }
+    // The lines after the first one may be decorated, as in ` * text`.
+    let decorated = block
+        .iter()
+        .filter(|(after_break, line, _)| *after_break && !line.is_empty())
+        .all(|(_, line, _)| line.trim_start().starts_with('*'));
+    for (after_break, line, at) in block {
+        let cut = match after_break && decorated && !line.is_empty() {
+            true => line.len() - line.trim_start().len() + 1,
+            false => 0,
+        };
+        lines.push((line[cut..].to_owned(), start + offset(at + cut)));
+    }
+}
+
+fn offset(len: usize) -> TextSize {
+    TextSize::try_from(len).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SourceFile, ast::HasDocComments};
+
+    #[track_caller]
+    fn check<N: HasDocComments>(text: &str, expected: &str) {
+        let file = SourceFile::parse(text);
+        let node = file.syntax_node().descendants().find_map(N::cast).unwrap();
+        let docs = node.doc_comments().unwrap();
+        assert_eq!(docs.text(), expected);
+        // Every line of the text is where the source says it is.
+        for (line, range) in docs.text().split('\n').zip(docs.source_ranges()) {
+            assert_eq!(&text[range], line);
+        }
+    }
+
+    #[test]
+    fn line_docs() {
+        check::<ast::LetDecl>(
+            "// Not a doc.\n/// The answer:\n///\n///     42\n[<Inline>]\nlet answer = 42",
+            "The answer:\n\n    42",
+        );
+        check::<ast::Import>("/// Lists.\nimport Std.List", "Lists.");
+        check::<ast::Where>(
+            "/// Equality.\nwhere EqInt Eq[Int] =\n    let eq x y = true",
+            "Equality.",
+        );
+    }
+
+    #[test]
+    fn block_docs() {
+        check::<ast::Union>(
+            "/** An option. */\nunion Option [a] = Some[a] | None",
+            "An option.",
+        );
+        check::<ast::Alias>(
+            "/**\n * A list.\n *\n * - of `a`s\n */\nalias L [a] = List[a]",
+            "A list.\n\n- of `a`s",
+        );
+        check::<ast::Value>(
+            "union A = A\n/** Second,\n    indented. */\nand value B\n/// Third.\nand alias C = B",
+            "Second,\n   indented.",
+        );
+        check::<ast::Alias>("union A = A\n\n/// Third.\nand alias C = A", "Third.");
+    }
+
+    #[test]
+    fn inner_docs() {
+        check::<ast::SourceFile>("//! The file.\n//! Docs.\nlet x", "The file.\nDocs.");
+        check::<ast::Module>(
+            "/// Outer.\nmodule M =\n    /*! Inner. */\n    let x",
+            "Outer.\nInner.",
+        );
+    }
+
+    #[test]
+    fn map_ranges() {
+        let text = "/// Foo `x`.\n///   bar\nlet x";
+        let file = SourceFile::parse(text).tree();
+        let let_decl = file
+            .syntax()
+            .descendants()
+            .find_map(ast::LetDecl::cast)
+            .unwrap();
+        let docs = let_decl.doc_comments().unwrap();
+        assert_eq!(docs.text(), "Foo `x`.\n  bar");
+        let x = TextRange::at(5.into(), 1.into());
+        assert_eq!(&docs.text()[x], "x");
+        assert_eq!(&text[docs.source_range(x).unwrap()], "x");
+        assert_eq!(docs.source_range(TextRange::new(5.into(), 12.into())), None);
+
+        let plain = SourceFile::parse("// Plain.\nlet x").tree();
+        let let_decl = plain.syntax().descendants().find_map(ast::LetDecl::cast);
+        assert_eq!(let_decl.unwrap().doc_comments(), None);
+    }
+}
@@ -0,0 +1,172 @@
+//! Indentation of nodes, which the offside rule makes a part of the syntax.
+//!
+//! See [`crate::ted`] for editing trees while keeping the indentation valid.
+
+use std::{fmt, ops};
+
+use crate::{
+    SyntaxElement,
+    SyntaxKind::{NEWLINE, WHITESPACE},
+    SyntaxNode, SyntaxToken,
+    ast::make,
+    ted,
+};
+
+/// The indentation of a line, in columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IndentLevel(pub usize);
+
+impl IndentLevel {
+    /// How much deeper a nested block is indented than its header.
+    pub const BLOCK: IndentLevel = IndentLevel(4);
+
+    /// The column where `element` starts.
+    pub fn from_element(element: &SyntaxElement) -> IndentLevel {
+        match element {
+            rowan::NodeOrToken::Node(it) => IndentLevel::from_node(it),
+            rowan::NodeOrToken::Token(it) => IndentLevel::column_of(it),
+        }
+    }
+
+    /// The column where `node` starts. The lines of a node are indented
+    /// relative to it, as the offside rule counts from there rather than from
+    /// the start of the line: in `let f = match x with`, the cases go under
+    /// `match`.
+    pub fn from_node(node: &SyntaxNode) -> IndentLevel {
+        match node.first_token() {
+            Some(it) => IndentLevel::column_of(&it),
+            None => IndentLevel(0),
+        }
+    }
+
+    /// The column where `token` starts.
+    pub fn column_of(token: &SyntaxToken) -> IndentLevel {
+        let mut res = 0;
+        let mut prev = token.prev_token();
+        while let Some(it) = prev {
+            if it.kind() == NEWLINE {
+                break;
+            }
+            // Block comments and strings may span lines.
+            match it.text().rfind('\n') {
+                Some(i) => {
+                    res += it.text()[i + 1..].len();
+                    break;
+                },
+                None => res += it.text().len(),
+            }
+            prev = it.prev_token();
+        }
+        IndentLevel(res)
+    }
+
+    /// The indentation of the line of `token`.
+    pub fn from_token(token: &SyntaxToken) -> IndentLevel {
+        let mut first = token.clone();
+        while let Some(prev) = first.prev_token() {
+            if prev.kind() == NEWLINE {
+                break;
+            }
+            first = prev;
+        }
+        match first.kind() {
+            WHITESPACE if first != *token => IndentLevel(first.text().len()),
+            _ => IndentLevel(0),
+        }
+    }
+
+    /// Indents the lines of the mutable `node` after the first one by `self`
+    /// more.
+    pub fn increase_indent(self, node: &SyntaxNode) {
+        self.shift(node, |len| len + self.0);
+    }
+
+    /// Indents the lines of the mutable `node` after the first one by `self`
+    /// less, or not at all if they aren't indented as much.
+    pub fn decrease_indent(self, node: &SyntaxNode) {
+        self.shift(node, |len| len.saturating_sub(self.0));
+    }
+
+    fn shift(self, node: &SyntaxNode, f: impl Fn(usize) -> usize) {
+        if self.0 == 0 {
+            return;
+        }
+        let range = node.text_range();
+        let line_starts: Vec<SyntaxToken> = node
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| it.kind() == NEWLINE)
+            .filter_map(|it| it.next_token())
+            .filter(|it| range.contains_range(it.text_range()) && it.kind() != NEWLINE)
+            .collect();
+        for token in line_starts {
+            let (len, rest) = match token.kind() {
+                WHITESPACE => (token.text().len(), None),
+                _ => (0, Some(token.clone())),
+            };
+            let ws = match f(len) {
+                0 => None,
+                len => Some(make::tokens::whitespace(&" ".repeat(len))),
+            };
+            match (ws, rest) {
+                (Some(ws), Some(rest)) => ted::insert_raw(ted::Position::before(&rest), ws),
+                (Some(ws), None) => ted::replace_raw(&token, ws),
+                (None, None) => ted::remove_raw(&token),
+                (None, Some(_)) => (),
+            }
+        }
+    }
+}
+
+impl fmt::Display for IndentLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:width$}", "", width = self.0)
+    }
+}
+
+impl ops::Add for IndentLevel {
+    type Output = IndentLevel;
+    fn add(self, rhs: IndentLevel) -> IndentLevel {
+        IndentLevel(self.0 + rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AstNode, SourceFile, ast};
+
+    #[test]
+    fn indent_level() {
+        let file = SourceFile::parse("module M =\n    let f =\n        g x\n").tree();
+        let decl = file
+            .syntax()
+            .descendants()
+            .find_map(ast::LetDecl::cast)
+            .unwrap();
+        assert_eq!(IndentLevel::from_node(decl.syntax()), IndentLevel(4));
+        let app = file
+            .syntax()
+            .descendants()
+            .find_map(ast::AppExpr::cast)
+            .unwrap();
+        assert_eq!(IndentLevel::from_node(app.syntax()), IndentLevel(8));
+        assert_eq!(IndentLevel::from_node(file.syntax()), IndentLevel(0));
+
+        let file = SourceFile::parse("module M =\n    let f = g x\n").tree();
+        let app = file
+            .syntax()
+            .descendants()
+            .find_map(ast::AppExpr::cast)
+            .unwrap();
+        assert_eq!(IndentLevel::from_node(app.syntax()), IndentLevel(12));
+        let first = app.syntax().first_token().unwrap();
+        assert_eq!(IndentLevel::from_token(&first), IndentLevel(4));
+
+        let decl = decl.clone_for_update();
+        IndentLevel(2).decrease_indent(decl.syntax());
+        assert_eq!(decl.to_string(), "let f =\n      g x");
+        IndentLevel(4).increase_indent(decl.syntax());
+        assert_eq!(decl.to_string(), "let f =\n          g x");
+    }
+}
@@ -0,0 +1,366 @@
+//! Various extension methods to ast expression nodes, which are
+//! hard to code-generate.
+
+use crate::{
+    SyntaxKind::{self, *},
+    SyntaxNode, SyntaxToken, T,
+    ast::{self, Associativity, AstNode, AstToken, BinaryOp, FStringPart, LiteralError, support},
+};
+
+impl ast::Expr {
+    /// Whether `self` needs parentheses to take the place of `place` in its
+    /// tree and still be parsed as one expression there, as `a + b` in place
+    /// of `x` in `x * c`.
+    pub fn needs_parens_in_place_of(&self, place: &ast::Expr) -> bool {
+        if self.is_open() && !is_at_end(place.syntax()) {
+            return true;
+        }
+        let Some(parent) = place.syntax().parent() else {
+            return false;
+        };
+        let is_first = |first: Option<ast::Expr>| first.is_some_and(|it| it == *place);
+        if let Some(bin) = ast::BinExpr::cast(parent.clone()) {
+            let Some(op) = bin.op_kind() else {
+                return false;
+            };
+            let tighter = match (op.associativity(), is_first(bin.lhs())) {
+                (Associativity::Left, true) | (Associativity::Right, false) => 0,
+                _ => 1,
+            };
+            return self.binding_power() < op.precedence() + tighter;
+        }
+        if let Some(app) = ast::AppExpr::cast(parent.clone()) {
+            return match is_first(app.fun()) {
+                true => !(self.is_atom() || matches!(self, ast::Expr::AppExpr(_))),
+                false => !self.is_atom(),
+            };
+        }
+        match parent.kind() {
+            UNARY_EXPR => self.binding_power() < u8::MAX,
+            ASC_EXPR => self.binding_power() == 0,
+            TUPLE_EXPR => matches!(self, ast::Expr::TupleExpr(_) | ast::Expr::SeqExpr(_)),
+            _ => false,
+        }
+    }
+
+    /// Whether the expression can be an argument without parentheses.
+    pub(crate) fn is_atom(&self) -> bool {
+        matches!(
+            self,
+            ast::Expr::PathExpr(_) | ast::Expr::Literal(_) | ast::Expr::ParenExpr(_)
+        )
+    }
+
+    /// How tightly the expression holds together as an operand of a binary
+    /// operator: the precedence of its operator, more than any operator for
+    /// applications and atoms, and less for the rest.
+    pub(crate) fn binding_power(&self) -> u8 {
+        match self {
+            ast::Expr::BinExpr(it) => it.op_kind().map_or(0, BinaryOp::precedence),
+            ast::Expr::PathExpr(_)
+            | ast::Expr::Literal(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::AppExpr(_)
+            | ast::Expr::UnaryExpr(_) => u8::MAX,
+            // `if` and `match` take everything after them into the last branch.
+            ast::Expr::IfExpr(_)
+            | ast::Expr::MatchExpr(_)
+            | ast::Expr::LetExpr(_)
+            | ast::Expr::SeqExpr(_)
+            | ast::Expr::TupleExpr(_)
+            | ast::Expr::AscExpr(_) => 0,
+        }
+    }
+
+    /// Whether the expression ends with an `if`, a `match` or a `let` that
+    /// would take what follows it, as in `a + if c then x else y`.
+    pub(crate) fn is_open(&self) -> bool {
+        match self {
+            ast::Expr::IfExpr(_) | ast::Expr::MatchExpr(_) | ast::Expr::LetExpr(_) => true,
+            ast::Expr::BinExpr(it) => it.rhs().is_some_and(|it| it.is_open()),
+            ast::Expr::UnaryExpr(it) => it.expr().is_some_and(|it| it.is_open()),
+            _ => false,
+        }
+    }
+}
+
+/// Whether nothing that an open expression would take can follow `node`:
+/// it ends its item, a line of a sequence or a parenthesized expression.
+fn is_at_end(node: &SyntaxNode) -> bool {
+    if ast::Item::can_cast(node.kind()) {
+        return true;
+    }
+    let Some(parent) = node.parent() else {
+        return true;
+    };
+    if matches!(parent.kind(), PAREN_EXPR | SEQ_EXPR) {
+        return true;
+    }
+    let last = parent
+        .children_with_tokens()
+        .filter(|it| !it.kind().is_trivia())
+        .last();
+    last.and_then(|it| it.into_node()).as_ref() == Some(node) && is_at_end(&parent)
+}
+
+impl ast::BinExpr {
+    pub fn lhs(&self) -> Option<ast::Expr> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn rhs(&self) -> Option<ast::Expr> {
+        support::children(self.syntax()).nth(1)
+    }
+
+    pub fn op(&self) -> Option<ast::Operator> {
+        support::child(self.syntax())
+    }
+
+    pub fn op_kind(&self) -> Option<BinaryOp> {
+        BinaryOp::from_kind(self.op()?.token()?.kind())
+    }
+}
+
+impl ast::UnaryExpr {
+    pub fn op(&self) -> Option<ast::Operator> {
+        support::child(self.syntax())
+    }
+}
+
+impl ast::Operator {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.syntax().first_token()
+    }
+}
+
+impl ast::AppExpr {
+    pub fn fun(&self) -> Option<ast::Expr> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn arg(&self) -> Option<ast::Expr> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::SeqExpr {
+    pub fn fst(&self) -> Option<ast::Expr> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn snd(&self) -> Option<ast::Expr> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::LetExpr {
+    pub fn body(&self) -> Option<ast::Expr> {
+        support::child(self.syntax())
+    }
+}
+
+impl ast::IfExpr {
+    pub fn condition(&self) -> Option<ast::Expr> {
+        self.expr_after(T![if])
+    }
+
+    pub fn then_branch(&self) -> Option<ast::Expr> {
+        self.expr_after(T![then])
+    }
+
+    pub fn else_branch(&self) -> Option<ast::Expr> {
+        self.expr_after(T![else])
+    }
+
+    /// The parts are told apart by the keywords, so that a missing expression
+    /// doesn't shift the other ones.
+    fn expr_after(&self, keyword: SyntaxKind) -> Option<ast::Expr> {
+        self.syntax()
+            .children_with_tokens()
+            .skip_while(|it| it.kind() != keyword)
+            .skip(1)
+            .take_while(|it| !matches!(it.kind(), T![if] | T![then] | T![else]))
+            .find_map(|it| it.into_node().and_then(ast::Expr::cast))
+    }
+}
+
+impl ast::MatchGuard {
+    pub fn condition(&self) -> Option<ast::Expr> {
+        support::child(self.syntax())
+    }
+}
+
+/// The value of a literal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralKind {
+    Int { value: u128, suffix: Option<String> },
+    Float(f64),
+    String(String),
+    FString(Vec<FStringPart>),
+    Char(char),
+    Bool(bool),
+}
+
+impl ast::Literal {
+    pub fn token(&self) -> SyntaxToken {
+        self.syntax()
+            .children_with_tokens()
+            .find(|e| !e.kind().is_trivia())
+            .and_then(|e| e.into_token())
+            .unwrap()
+    }
+
+    /// The value of the literal, or the first thing that is wrong with it.
+    pub fn kind(&self) -> Result<LiteralKind, LiteralError> {
+        let token = self.token();
+        let kind = match token.kind() {
+            INT_NUMBER => {
+                let int = ast::IntNumber::cast(token).unwrap();
+                LiteralKind::Int {
+                    value: int.value()?,
+                    suffix: int.suffix().map(str::to_owned),
+                }
+            },
+            FLOAT_NUMBER => LiteralKind::Float(ast::FloatNumber::cast(token).unwrap().value()?),
+            STRING => LiteralKind::String(ast::String::cast(token).unwrap().value()?),
+            F_STRING => LiteralKind::FString(ast::FString::cast(token).unwrap().parts()?),
+            CHAR => LiteralKind::Char(ast::Char::cast(token).unwrap().value()?),
+            T![true] => LiteralKind::Bool(true),
+            T![false] => LiteralKind::Bool(false),
+            kind => unreachable!("{kind:?} is not a literal"),
+        };
+        Ok(kind)
+    }
+
+    /// Everything that is wrong with the literal, unlike `kind` which stops at
+    /// the first error.
+    pub(crate) fn errors(&self) -> Vec<LiteralError> {
+        let token = self.token();
+        let res = match token.kind() {
+            STRING => return ast::String::cast(token).unwrap().errors(),
+            F_STRING => return ast::FString::cast(token).unwrap().errors(),
+            INT_NUMBER => return ast::IntNumber::cast(token).unwrap().errors(),
+            FLOAT_NUMBER => ast::FloatNumber::cast(token).unwrap().value().map(drop),
+            CHAR => ast::Char::cast(token).unwrap().value().map(drop),
+            _ => Ok(()),
+        };
+        res.err().into_iter().collect()
+    }
+}
+
+impl ast::LiteralPat {
+    /// The value of the literal that the pattern matches, see
+    /// [`ast::Literal::kind`].
+    pub fn kind(&self) -> Option<Result<LiteralKind, LiteralError>> {
+        self.literal().map(|it| it.kind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        SourceFile, TextRange,
+        ast::{Associativity, LiteralErrorKind},
+    };
+
+    fn kinds(text: &str) -> Vec<Result<LiteralKind, LiteralError>> {
+        let file = SourceFile::parse(text).tree();
+        file.syntax()
+            .descendants()
+            .filter_map(ast::Literal::cast)
+            .map(|it| it.kind())
+            .collect()
+    }
+
+    #[test]
+    fn literal_kind() {
+        let kinds = kinds(r#"let x = 92u8, 9.5, "a\n\"b\"", f"n = {n}\{", '\'', true"#);
+        assert_eq!(kinds, [
+            Ok(LiteralKind::Int {
+                value: 92,
+                suffix: Some("u8".to_owned())
+            }),
+            Ok(LiteralKind::Float(9.5)),
+            Ok(LiteralKind::String("a\n\"b\"".to_owned())),
+            Ok(LiteralKind::FString(vec![
+                FStringPart::Text("n = ".to_owned()),
+                FStringPart::Hole {
+                    text: "n".to_owned(),
+                    range: TextRange::new(38.into(), 39.into())
+                },
+                FStringPart::Text("{".to_owned()),
+            ])),
+            Ok(LiteralKind::Char('\'')),
+            Ok(LiteralKind::Bool(true)),
+        ]);
+    }
+
+    #[test]
+    fn int_suffix() {
+        let kinds = kinds("let x = 92foo, 1u128, 300u8");
+        assert_eq!(kinds, [
+            Err(LiteralError {
+                kind: LiteralErrorKind::InvalidSuffix,
+                range: TextRange::new(10.into(), 13.into()),
+            }),
+            Ok(LiteralKind::Int {
+                value: 1,
+                suffix: Some("u128".to_owned())
+            }),
+            Ok(LiteralKind::Int {
+                value: 300,
+                suffix: Some("u8".to_owned())
+            }),
+        ]);
+    }
+
+    fn find<N: AstNode>(text: &str) -> N {
+        let file = SourceFile::parse(text).tree();
+        file.syntax().descendants().find_map(N::cast).unwrap()
+    }
+
+    #[test]
+    fn positional_children() {
+        let bin: ast::BinExpr = find("let x = a :: b :: c");
+        assert_eq!(bin.lhs().unwrap().syntax().text(), "a");
+        assert_eq!(bin.rhs().unwrap().syntax().text(), "b :: c");
+        let op = bin.op_kind().unwrap();
+        assert_eq!(
+            (op, op.precedence(), op.associativity()),
+            (BinaryOp::Cons, 4, Associativity::Right)
+        );
+
+        let bin: ast::BinExpr = find("let x = a <> b");
+        assert_eq!(bin.op_kind(), Some(BinaryOp::Neq));
+
+        let app: ast::AppExpr = find("let x = f y z");
+        assert_eq!(app.fun().unwrap().syntax().text(), "f y");
+        assert_eq!(app.arg().unwrap().syntax().text(), "z");
+
+        let seq: ast::SeqExpr = find("let main =\n    print a\n    print b");
+        assert_eq!(seq.fst().unwrap().syntax().text(), "print a");
+        assert_eq!(seq.snd().unwrap().syntax().text(), "print b");
+
+        let if_expr: ast::IfExpr = find("let x = if c then a else b");
+        assert_eq!(if_expr.condition().unwrap().syntax().text(), "c");
+        assert_eq!(if_expr.then_branch().unwrap().syntax().text(), "a");
+        assert_eq!(if_expr.else_branch().unwrap().syntax().text(), "b");
+
+        let if_expr: ast::IfExpr = find("let x = if then a");
+        assert!(if_expr.condition().is_none());
+        assert_eq!(if_expr.then_branch().unwrap().syntax().text(), "a");
+        assert!(if_expr.else_branch().is_none());
+    }
+
+    #[test]
+    fn literal_pat_kind() {
+        let file = SourceFile::parse("let f x = match x with | 'a' -> 1 | _ -> 0").tree();
+        let pat = file
+            .syntax()
+            .descendants()
+            .find_map(ast::LiteralPat::cast)
+            .unwrap();
+        assert_eq!(pat.kind(), Some(Ok(LiteralKind::Char('a'))));
+    }
+}
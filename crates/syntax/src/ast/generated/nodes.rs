@@ -19,10 +19,11 @@ impl AdtList {
 pub struct Alias {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for Alias {}
+impl ast::HasConstraintList for Alias {}
+impl ast::HasDocComments for Alias {}
+impl ast::HasName for Alias {}
 impl Alias {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
-    pub fn constraint_list(&self) -> Option<ConstraintList> { support::child(&self.syntax) }
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn ty(&self) -> Option<Type> { support::child(&self.syntax) }
     pub fn eq_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![=]) }
     pub fn alias_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![alias]) }
@@ -32,10 +33,7 @@ impl Alias {
 pub struct AppExpr {
     pub(crate) syntax: SyntaxNode,
 }
-impl AppExpr {
-    pub fn arg(&self) -> Option<Expr> { support::child(&self.syntax) }
-    pub fn fun(&self) -> Option<Expr> { support::child(&self.syntax) }
-}
+impl AppExpr {}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AscExpr {
@@ -71,9 +69,10 @@ impl Attr {
 pub struct AttrDef {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for AttrDef {}
+impl ast::HasDocComments for AttrDef {}
+impl ast::HasName for AttrDef {}
 impl AttrDef {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn attribute_token(&self) -> Option<SyntaxToken> {
         support::token(&self.syntax, T![attribute])
     }
@@ -89,12 +88,13 @@ impl BinExpr {}
 pub struct Class {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for Class {}
+impl ast::HasConstraintList for Class {}
+impl ast::HasDocComments for Class {}
+impl ast::HasName for Class {}
+impl ast::HasTypeVars for Class {}
 impl Class {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
-    pub fn constraint_list(&self) -> Option<ConstraintList> { support::child(&self.syntax) }
     pub fn functions(&self) -> AstChildren<LetDecl> { support::children(&self.syntax) }
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
-    pub fn type_vars(&self) -> AstChildren<TypeVar> { support::children(&self.syntax) }
     pub fn eq_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![=]) }
     pub fn class_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![class]) }
 }
@@ -104,8 +104,6 @@ pub struct ConsPat {
     pub(crate) syntax: SyntaxNode,
 }
 impl ConsPat {
-    pub fn head(&self) -> Option<Pat> { support::child(&self.syntax) }
-    pub fn rest(&self) -> Option<Pat> { support::child(&self.syntax) }
     pub fn colon2_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![::]) }
 }
 
@@ -113,9 +111,9 @@ impl ConsPat {
 pub struct Constraint {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasTypeVars for Constraint {}
 impl Constraint {
     pub fn class(&self) -> Option<Path> { support::child(&self.syntax) }
-    pub fn type_vars(&self) -> AstChildren<TypeVar> { support::children(&self.syntax) }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -132,8 +130,8 @@ impl ConstraintList {
 pub struct ConstraintType {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasConstraintList for ConstraintType {}
 impl ConstraintType {
-    pub fn constraint_list(&self) -> Option<ConstraintList> { support::child(&self.syntax) }
     pub fn ty(&self) -> Option<Type> { support::child(&self.syntax) }
 }
 
@@ -142,8 +140,6 @@ pub struct FnType {
     pub(crate) syntax: SyntaxNode,
 }
 impl FnType {
-    pub fn arg(&self) -> Option<Type> { support::child(&self.syntax) }
-    pub fn ret(&self) -> Option<Type> { support::child(&self.syntax) }
     pub fn arrow_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![->]) }
 }
 
@@ -151,8 +147,8 @@ impl FnType {
 pub struct IdentPat {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasName for IdentPat {}
 impl IdentPat {
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn pat(&self) -> Option<Pat> { support::child(&self.syntax) }
     pub fn as_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![as]) }
 }
@@ -171,8 +167,9 @@ impl IfExpr {
 pub struct Import {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for Import {}
+impl ast::HasDocComments for Import {}
 impl Import {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
     pub fn path(&self) -> Option<Path> { support::child(&self.syntax) }
     pub fn import_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![import]) }
 }
@@ -189,12 +186,13 @@ impl InferType {
 pub struct LetDecl {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for LetDecl {}
+impl ast::HasConstraintList for LetDecl {}
+impl ast::HasDocComments for LetDecl {}
+impl ast::HasParamList for LetDecl {}
 impl LetDecl {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
-    pub fn constraint_list(&self) -> Option<ConstraintList> { support::child(&self.syntax) }
     pub fn expr(&self) -> Option<Expr> { support::child(&self.syntax) }
     pub fn let_op(&self) -> Option<LetOp> { support::child(&self.syntax) }
-    pub fn param_list(&self) -> Option<ParamList> { support::child(&self.syntax) }
     pub fn pat(&self) -> Option<Pat> { support::child(&self.syntax) }
     pub fn ty(&self) -> Option<Type> { support::child(&self.syntax) }
     pub fn colon_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![:]) }
@@ -271,10 +269,11 @@ impl MatchGuard {
 pub struct Module {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for Module {}
+impl ast::HasDocComments for Module {}
+impl ast::HasName for Module {}
 impl Module {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
     pub fn items(&self) -> AstChildren<Item> { support::children(&self.syntax) }
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn eq_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![=]) }
     pub fn module_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![module]) }
 }
@@ -288,12 +287,24 @@ impl Name {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Operator {
+pub struct OnlyRecordPat {
     pub(crate) syntax: SyntaxNode,
 }
-impl Operator {
-    pub fn star_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![*]) }
+impl OnlyRecordPat {
+    pub fn fields(&self) -> AstChildren<RecordPatField> { support::children(&self.syntax) }
+    pub fn l_curly_pipe_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T!["{|"])
+    }
+    pub fn pipe_r_curly_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T!["|}"])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Operator {
+    pub(crate) syntax: SyntaxNode,
 }
+impl Operator {}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct OrPat {
@@ -364,12 +375,20 @@ impl Path {
     pub fn dot_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![.]) }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+impl PathExpr {
+    pub fn path(&self) -> Option<Path> { support::child(&self.syntax) }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PathSegment {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasName for PathSegment {}
 impl PathSegment {
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn type_arg_list(&self) -> Option<TypeArgList> { support::child(&self.syntax) }
 }
 
@@ -404,8 +423,8 @@ impl RecordPat {
 pub struct RecordPatField {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasName for RecordPatField {}
 impl RecordPatField {
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn pat(&self) -> Option<Pat> { support::child(&self.syntax) }
     pub fn eq_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![=]) }
 }
@@ -414,15 +433,13 @@ impl RecordPatField {
 pub struct SeqExpr {
     pub(crate) syntax: SyntaxNode,
 }
-impl SeqExpr {
-    pub fn fst(&self) -> Option<Expr> { support::child(&self.syntax) }
-    pub fn snd(&self) -> Option<Expr> { support::child(&self.syntax) }
-}
+impl SeqExpr {}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SourceFile {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasDocComments for SourceFile {}
 impl SourceFile {
     pub fn items(&self) -> AstChildren<Item> { support::children(&self.syntax) }
     pub fn preamble(&self) -> Option<Preamble> { support::child(&self.syntax) }
@@ -493,11 +510,12 @@ impl UnaryExpr {
 pub struct Union {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for Union {}
+impl ast::HasConstraintList for Union {}
+impl ast::HasDocComments for Union {}
+impl ast::HasName for Union {}
 impl Union {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
     pub fn cases(&self) -> AstChildren<Path> { support::children(&self.syntax) }
-    pub fn constraint_list(&self) -> Option<ConstraintList> { support::child(&self.syntax) }
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn eq_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![=]) }
     pub fn union_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![union]) }
     pub fn pipe_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![|]) }
@@ -507,11 +525,12 @@ impl Union {
 pub struct Value {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for Value {}
+impl ast::HasConstraintList for Value {}
+impl ast::HasDocComments for Value {}
+impl ast::HasName for Value {}
 impl Value {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
-    pub fn constraint_list(&self) -> Option<ConstraintList> { support::child(&self.syntax) }
     pub fn fields(&self) -> AstChildren<ValueField> { support::children(&self.syntax) }
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn l_paren_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T!['(']) }
     pub fn r_paren_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![')']) }
     pub fn value_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![value]) }
@@ -521,8 +540,8 @@ impl Value {
 pub struct ValueField {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasName for ValueField {}
 impl ValueField {
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn ty(&self) -> Option<Type> { support::child(&self.syntax) }
     pub fn colon_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![:]) }
 }
@@ -531,11 +550,12 @@ impl ValueField {
 pub struct Where {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::HasAttrs for Where {}
+impl ast::HasConstraintList for Where {}
+impl ast::HasDocComments for Where {}
+impl ast::HasName for Where {}
 impl Where {
-    pub fn attrs(&self) -> AstChildren<Attr> { support::children(&self.syntax) }
-    pub fn constraint_list(&self) -> Option<ConstraintList> { support::child(&self.syntax) }
     pub fn functions(&self) -> AstChildren<LetDecl> { support::children(&self.syntax) }
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
     pub fn path(&self) -> Option<Path> { support::child(&self.syntax) }
     pub fn eq_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![=]) }
     pub fn where_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![where]) }
@@ -555,6 +575,10 @@ pub enum Adt {
     Union(Union),
     Value(Value),
 }
+impl ast::HasAttrs for Adt {}
+impl ast::HasConstraintList for Adt {}
+impl ast::HasDocComments for Adt {}
+impl ast::HasName for Adt {}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Expr {
@@ -566,6 +590,7 @@ pub enum Expr {
     Literal(Literal),
     MatchExpr(MatchExpr),
     ParenExpr(ParenExpr),
+    PathExpr(PathExpr),
     SeqExpr(SeqExpr),
     TupleExpr(TupleExpr),
     UnaryExpr(UnaryExpr),
@@ -588,6 +613,7 @@ pub enum Pat {
     ConsPat(ConsPat),
     IdentPat(IdentPat),
     LiteralPat(LiteralPat),
+    OnlyRecordPat(OnlyRecordPat),
     OrPat(OrPat),
     ParenPat(ParenPat),
     RecordPat(RecordPat),
@@ -605,6 +631,41 @@ pub enum Type {
     PathType(PathType),
     TupleType(TupleType),
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnyHasAttrs {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ast::HasAttrs for AnyHasAttrs {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnyHasConstraintList {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ast::HasConstraintList for AnyHasConstraintList {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnyHasDocComments {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ast::HasDocComments for AnyHasDocComments {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnyHasName {
+    pub(crate) syntax: SyntaxNode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnyHasParamList {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ast::HasParamList for AnyHasParamList {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnyHasTypeVars {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ast::HasTypeVars for AnyHasTypeVars {}
 impl AstNode for AdtList {
     fn can_cast(kind: SyntaxKind) -> bool { kind == ADT_LIST }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
@@ -913,6 +974,17 @@ impl AstNode for Name {
     }
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl AstNode for OnlyRecordPat {
+    fn can_cast(kind: SyntaxKind) -> bool { kind == ONLY_RECORD_PAT }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
 impl AstNode for Operator {
     fn can_cast(kind: SyntaxKind) -> bool { kind == OPERATOR }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
@@ -1001,6 +1073,17 @@ impl AstNode for Path {
     }
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl AstNode for PathExpr {
+    fn can_cast(kind: SyntaxKind) -> bool { kind == PATH_EXPR }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
 impl AstNode for PathSegment {
     fn can_cast(kind: SyntaxKind) -> bool { kind == PATH_SEGMENT }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
@@ -1262,6 +1345,9 @@ impl From<MatchExpr> for Expr {
 impl From<ParenExpr> for Expr {
     fn from(node: ParenExpr) -> Expr { Expr::ParenExpr(node) }
 }
+impl From<PathExpr> for Expr {
+    fn from(node: PathExpr) -> Expr { Expr::PathExpr(node) }
+}
 impl From<SeqExpr> for Expr {
     fn from(node: SeqExpr) -> Expr { Expr::SeqExpr(node) }
 }
@@ -1283,6 +1369,7 @@ impl AstNode for Expr {
                 | LITERAL
                 | MATCH_EXPR
                 | PAREN_EXPR
+                | PATH_EXPR
                 | SEQ_EXPR
                 | TUPLE_EXPR
                 | UNARY_EXPR
@@ -1298,6 +1385,7 @@ impl AstNode for Expr {
             LITERAL => Expr::Literal(Literal { syntax }),
             MATCH_EXPR => Expr::MatchExpr(MatchExpr { syntax }),
             PAREN_EXPR => Expr::ParenExpr(ParenExpr { syntax }),
+            PATH_EXPR => Expr::PathExpr(PathExpr { syntax }),
             SEQ_EXPR => Expr::SeqExpr(SeqExpr { syntax }),
             TUPLE_EXPR => Expr::TupleExpr(TupleExpr { syntax }),
             UNARY_EXPR => Expr::UnaryExpr(UnaryExpr { syntax }),
@@ -1315,6 +1403,7 @@ impl AstNode for Expr {
             Expr::Literal(it) => &it.syntax,
             Expr::MatchExpr(it) => &it.syntax,
             Expr::ParenExpr(it) => &it.syntax,
+            Expr::PathExpr(it) => &it.syntax,
             Expr::SeqExpr(it) => &it.syntax,
             Expr::TupleExpr(it) => &it.syntax,
             Expr::UnaryExpr(it) => &it.syntax,
@@ -1386,6 +1475,9 @@ impl From<IdentPat> for Pat {
 impl From<LiteralPat> for Pat {
     fn from(node: LiteralPat) -> Pat { Pat::LiteralPat(node) }
 }
+impl From<OnlyRecordPat> for Pat {
+    fn from(node: OnlyRecordPat) -> Pat { Pat::OnlyRecordPat(node) }
+}
 impl From<OrPat> for Pat {
     fn from(node: OrPat) -> Pat { Pat::OrPat(node) }
 }
@@ -1412,6 +1504,7 @@ impl AstNode for Pat {
                 | CONS_PAT
                 | IDENT_PAT
                 | LITERAL_PAT
+                | ONLY_RECORD_PAT
                 | OR_PAT
                 | PAREN_PAT
                 | RECORD_PAT
@@ -1426,6 +1519,7 @@ impl AstNode for Pat {
             CONS_PAT => Pat::ConsPat(ConsPat { syntax }),
             IDENT_PAT => Pat::IdentPat(IdentPat { syntax }),
             LITERAL_PAT => Pat::LiteralPat(LiteralPat { syntax }),
+            ONLY_RECORD_PAT => Pat::OnlyRecordPat(OnlyRecordPat { syntax }),
             OR_PAT => Pat::OrPat(OrPat { syntax }),
             PAREN_PAT => Pat::ParenPat(ParenPat { syntax }),
             RECORD_PAT => Pat::RecordPat(RecordPat { syntax }),
@@ -1442,6 +1536,7 @@ impl AstNode for Pat {
             Pat::ConsPat(it) => &it.syntax,
             Pat::IdentPat(it) => &it.syntax,
             Pat::LiteralPat(it) => &it.syntax,
+            Pat::OnlyRecordPat(it) => &it.syntax,
             Pat::OrPat(it) => &it.syntax,
             Pat::ParenPat(it) => &it.syntax,
             Pat::RecordPat(it) => &it.syntax,
@@ -1499,6 +1594,482 @@ impl AstNode for Type {
         }
     }
 }
+impl AnyHasAttrs {
+    #[inline]
+    pub fn new<T: ast::HasAttrs>(node: T) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax().clone(),
+        }
+    }
+}
+impl AstNode for AnyHasAttrs {
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            ALIAS | ATTR_DEF | CLASS | IMPORT | LET_DECL | MODULE | UNION | VALUE | WHERE
+        )
+    }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        Self::can_cast(syntax.kind()).then_some(AnyHasAttrs { syntax })
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl From<Alias> for AnyHasAttrs {
+    #[inline]
+    fn from(node: Alias) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<AttrDef> for AnyHasAttrs {
+    #[inline]
+    fn from(node: AttrDef) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Class> for AnyHasAttrs {
+    #[inline]
+    fn from(node: Class) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Import> for AnyHasAttrs {
+    #[inline]
+    fn from(node: Import) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<LetDecl> for AnyHasAttrs {
+    #[inline]
+    fn from(node: LetDecl) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Module> for AnyHasAttrs {
+    #[inline]
+    fn from(node: Module) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Union> for AnyHasAttrs {
+    #[inline]
+    fn from(node: Union) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Value> for AnyHasAttrs {
+    #[inline]
+    fn from(node: Value) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Where> for AnyHasAttrs {
+    #[inline]
+    fn from(node: Where) -> AnyHasAttrs {
+        AnyHasAttrs {
+            syntax: node.syntax,
+        }
+    }
+}
+impl AnyHasConstraintList {
+    #[inline]
+    pub fn new<T: ast::HasConstraintList>(node: T) -> AnyHasConstraintList {
+        AnyHasConstraintList {
+            syntax: node.syntax().clone(),
+        }
+    }
+}
+impl AstNode for AnyHasConstraintList {
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            ALIAS | CLASS | CONSTRAINT_TYPE | LET_DECL | UNION | VALUE | WHERE
+        )
+    }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        Self::can_cast(syntax.kind()).then_some(AnyHasConstraintList { syntax })
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl From<Alias> for AnyHasConstraintList {
+    #[inline]
+    fn from(node: Alias) -> AnyHasConstraintList {
+        AnyHasConstraintList {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Class> for AnyHasConstraintList {
+    #[inline]
+    fn from(node: Class) -> AnyHasConstraintList {
+        AnyHasConstraintList {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<ConstraintType> for AnyHasConstraintList {
+    #[inline]
+    fn from(node: ConstraintType) -> AnyHasConstraintList {
+        AnyHasConstraintList {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<LetDecl> for AnyHasConstraintList {
+    #[inline]
+    fn from(node: LetDecl) -> AnyHasConstraintList {
+        AnyHasConstraintList {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Union> for AnyHasConstraintList {
+    #[inline]
+    fn from(node: Union) -> AnyHasConstraintList {
+        AnyHasConstraintList {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Value> for AnyHasConstraintList {
+    #[inline]
+    fn from(node: Value) -> AnyHasConstraintList {
+        AnyHasConstraintList {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Where> for AnyHasConstraintList {
+    #[inline]
+    fn from(node: Where) -> AnyHasConstraintList {
+        AnyHasConstraintList {
+            syntax: node.syntax,
+        }
+    }
+}
+impl AnyHasDocComments {
+    #[inline]
+    pub fn new<T: ast::HasDocComments>(node: T) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax().clone(),
+        }
+    }
+}
+impl AstNode for AnyHasDocComments {
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            ALIAS
+                | ATTR_DEF
+                | CLASS
+                | IMPORT
+                | LET_DECL
+                | MODULE
+                | SOURCE_FILE
+                | UNION
+                | VALUE
+                | WHERE
+        )
+    }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        Self::can_cast(syntax.kind()).then_some(AnyHasDocComments { syntax })
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl From<Alias> for AnyHasDocComments {
+    #[inline]
+    fn from(node: Alias) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<AttrDef> for AnyHasDocComments {
+    #[inline]
+    fn from(node: AttrDef) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Class> for AnyHasDocComments {
+    #[inline]
+    fn from(node: Class) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Import> for AnyHasDocComments {
+    #[inline]
+    fn from(node: Import) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<LetDecl> for AnyHasDocComments {
+    #[inline]
+    fn from(node: LetDecl) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Module> for AnyHasDocComments {
+    #[inline]
+    fn from(node: Module) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<SourceFile> for AnyHasDocComments {
+    #[inline]
+    fn from(node: SourceFile) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Union> for AnyHasDocComments {
+    #[inline]
+    fn from(node: Union) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Value> for AnyHasDocComments {
+    #[inline]
+    fn from(node: Value) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Where> for AnyHasDocComments {
+    #[inline]
+    fn from(node: Where) -> AnyHasDocComments {
+        AnyHasDocComments {
+            syntax: node.syntax,
+        }
+    }
+}
+impl AnyHasName {
+    #[inline]
+    pub fn new<T: ast::HasName>(node: T) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax().clone(),
+        }
+    }
+}
+impl AstNode for AnyHasName {
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            ALIAS
+                | ATTR_DEF
+                | CLASS
+                | IDENT_PAT
+                | LET_DECL
+                | MODULE
+                | PATH_SEGMENT
+                | RECORD_PAT_FIELD
+                | UNION
+                | VALUE
+                | VALUE_FIELD
+                | WHERE
+        )
+    }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        Self::can_cast(syntax.kind()).then_some(AnyHasName { syntax })
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl From<Alias> for AnyHasName {
+    #[inline]
+    fn from(node: Alias) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<AttrDef> for AnyHasName {
+    #[inline]
+    fn from(node: AttrDef) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Class> for AnyHasName {
+    #[inline]
+    fn from(node: Class) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<IdentPat> for AnyHasName {
+    #[inline]
+    fn from(node: IdentPat) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<LetDecl> for AnyHasName {
+    #[inline]
+    fn from(node: LetDecl) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Module> for AnyHasName {
+    #[inline]
+    fn from(node: Module) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<PathSegment> for AnyHasName {
+    #[inline]
+    fn from(node: PathSegment) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<RecordPatField> for AnyHasName {
+    #[inline]
+    fn from(node: RecordPatField) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Union> for AnyHasName {
+    #[inline]
+    fn from(node: Union) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Value> for AnyHasName {
+    #[inline]
+    fn from(node: Value) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<ValueField> for AnyHasName {
+    #[inline]
+    fn from(node: ValueField) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Where> for AnyHasName {
+    #[inline]
+    fn from(node: Where) -> AnyHasName {
+        AnyHasName {
+            syntax: node.syntax,
+        }
+    }
+}
+impl AnyHasParamList {
+    #[inline]
+    pub fn new<T: ast::HasParamList>(node: T) -> AnyHasParamList {
+        AnyHasParamList {
+            syntax: node.syntax().clone(),
+        }
+    }
+}
+impl AstNode for AnyHasParamList {
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { matches!(kind, LET_DECL) }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        Self::can_cast(syntax.kind()).then_some(AnyHasParamList { syntax })
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl From<LetDecl> for AnyHasParamList {
+    #[inline]
+    fn from(node: LetDecl) -> AnyHasParamList {
+        AnyHasParamList {
+            syntax: node.syntax,
+        }
+    }
+}
+impl AnyHasTypeVars {
+    #[inline]
+    pub fn new<T: ast::HasTypeVars>(node: T) -> AnyHasTypeVars {
+        AnyHasTypeVars {
+            syntax: node.syntax().clone(),
+        }
+    }
+}
+impl AstNode for AnyHasTypeVars {
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { matches!(kind, CLASS | CONSTRAINT) }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        Self::can_cast(syntax.kind()).then_some(AnyHasTypeVars { syntax })
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl From<Class> for AnyHasTypeVars {
+    #[inline]
+    fn from(node: Class) -> AnyHasTypeVars {
+        AnyHasTypeVars {
+            syntax: node.syntax,
+        }
+    }
+}
+impl From<Constraint> for AnyHasTypeVars {
+    #[inline]
+    fn from(node: Constraint) -> AnyHasTypeVars {
+        AnyHasTypeVars {
+            syntax: node.syntax,
+        }
+    }
+}
 impl std::fmt::Display for Adt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.syntax(), f)
@@ -1664,6 +2235,11 @@ impl std::fmt::Display for Name {
         std::fmt::Display::fmt(self.syntax(), f)
     }
 }
+impl std::fmt::Display for OnlyRecordPat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
 impl std::fmt::Display for Operator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.syntax(), f)
@@ -1704,6 +2280,11 @@ impl std::fmt::Display for Path {
         std::fmt::Display::fmt(self.syntax(), f)
     }
 }
+impl std::fmt::Display for PathExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
 impl std::fmt::Display for PathSegment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.syntax(), f)
@@ -0,0 +1,501 @@
+//! Free-standing functions for creating AST fragments out of smaller pieces.
+//!
+//! The fragments are made by printing a template and parsing it back, so they
+//! are always what the parser would produce for the same text. The results are
+//! roots of their own trees; use [`AstNode::clone_for_update`] to get a tree
+//! that can be edited.
+//!
+//! Nodes that span several lines are laid out so that the offside rule holds:
+//! the lines after the first one are indented relative to the first one, and
+//! the pieces passed in are re-indented to match.
+
+use itertools::Itertools;
+
+use crate::{
+    SourceFile, SyntaxKind, SyntaxNode,
+    ast::{self, Associativity, AstNode, BinaryOp, edit::IndentLevel},
+};
+
+/// The indentation of a nested block.
+const INDENT: usize = 4;
+
+pub fn name(text: &str) -> ast::Name {
+    ast_from_text(&format!("let {text}"))
+}
+
+pub fn path_segment(name: ast::Name) -> ast::PathSegment {
+    ast_from_text(&format!("alias X = {name}"))
+}
+
+pub fn path(segments: impl IntoIterator<Item = ast::PathSegment>) -> ast::Path {
+    let segments = segments.into_iter().join(".");
+    ast_from_text(&format!("alias X = {segments}"))
+}
+
+pub fn path_from_text(text: &str) -> ast::Path {
+    ast_from_text(&format!("alias X = {text}"))
+}
+
+pub fn ident_pat(name: ast::Name) -> ast::IdentPat {
+    ast_from_text(&format!("let {name}"))
+}
+
+pub fn wildcard_pat() -> ast::WildcardPat {
+    ast_from_text("let _")
+}
+
+pub fn param_list(pats: impl IntoIterator<Item = ast::Pat>) -> ast::ParamList {
+    let pats = pats.into_iter().map(|pat| {
+        match pat {
+            // Only atoms can be parameters, as in `let f x (y :: ys)`.
+            ast::Pat::IdentPat(_)
+            | ast::Pat::WildcardPat(_)
+            | ast::Pat::LiteralPat(_)
+            | ast::Pat::ParenPat(_)
+            | ast::Pat::RecordPat(_) => pat.to_string(),
+            _ => format!("({pat})"),
+        }
+    });
+    ast_from_text(&format!("let f {}", pats.format(" ")))
+}
+
+pub fn expr_path(path: ast::Path) -> ast::Expr {
+    expr_from_text(&path.to_string())
+}
+
+pub fn expr_literal(text: &str) -> ast::Literal {
+    ast_from_text(&format!("let x = {text}"))
+}
+
+pub fn expr_paren(expr: ast::Expr) -> ast::Expr {
+    expr_from_text(&format!("({})", reindent(expr.syntax(), 1)))
+}
+
+/// `fun arg`, with parentheses around `arg` unless it is an atom.
+pub fn expr_app(fun: ast::Expr, arg: ast::Expr) -> ast::Expr {
+    let fun_parens = !(fun.is_atom() || matches!(fun, ast::Expr::AppExpr(_)));
+    let arg_parens = !arg.is_atom();
+    let fun = operand(fun.syntax(), fun_parens, 0);
+    let arg = operand(arg.syntax(), arg_parens, last_line_len(&fun) + 1);
+    expr_from_text(&format!("{fun} {arg}"))
+}
+
+/// `lhs op rhs`, with parentheses around the operands that would otherwise
+/// not be the operands of `op`, as `a + b` in `(a + b) * c`.
+pub fn expr_bin(lhs: ast::Expr, op: BinaryOp, rhs: ast::Expr) -> ast::Expr {
+    let (lhs_bp, rhs_bp) = match op.associativity() {
+        Associativity::Left => (op.precedence(), op.precedence() + 1),
+        Associativity::Right => (op.precedence() + 1, op.precedence()),
+    };
+    let lhs_parens = lhs.binding_power() < lhs_bp || lhs.is_open();
+    let rhs_parens = rhs.binding_power() < rhs_bp;
+    let lhs = operand(lhs.syntax(), lhs_parens, 0);
+    let column = last_line_len(&lhs) + op.to_string().len() + 2;
+    let rhs = operand(rhs.syntax(), rhs_parens, column);
+    expr_from_text(&format!("{lhs} {op} {rhs}"))
+}
+
+/// A local `let`, as in `let x = 1 in x`, with the body on the next line
+/// when either part spans several lines.
+pub fn expr_let(let_decl: ast::LetDecl, body: ast::Expr) -> ast::Expr {
+    let let_decl = reindent(let_decl.syntax(), 0);
+    let body = reindent(body.syntax(), 0);
+    let sep = match let_decl.contains('\n') || body.contains('\n') {
+        true => "\n",
+        false => " ",
+    };
+    expr_from_text(&format!("{let_decl} in{sep}{body}"))
+}
+
+pub fn match_expr(
+    scrutinee: ast::Expr,
+    cases: impl IntoIterator<Item = ast::MatchCase>,
+) -> ast::MatchExpr {
+    let cases = cases
+        .into_iter()
+        .map(|case| format!("| {}", reindent(case.syntax(), 0)))
+        .join("\n");
+    let scrutinee = reindent(scrutinee.syntax(), "match ".len());
+    let expr = expr_from_text(&format!("match {scrutinee} with\n{cases}"));
+    ast::MatchExpr::cast(expr.syntax().clone()).unwrap()
+}
+
+pub fn match_case(pat: ast::Pat, guard: Option<ast::Expr>, expr: ast::Expr) -> ast::MatchCase {
+    let guard = match guard {
+        Some(guard) => {
+            let column = last_line_len(&format!("| {pat} when "));
+            format!(" when {}", reindent(guard.syntax(), column))
+        },
+        None => String::new(),
+    };
+    let head = format!("{pat}{guard} ->");
+    let case = format!("{head}{}", rhs(expr.syntax()));
+    let expr = expr_from_text(&format!("match x with\n| {case}"));
+    expr.syntax()
+        .descendants()
+        .find_map(ast::MatchCase::cast)
+        .unwrap()
+        .clone_subtree()
+}
+
+/// `let name params = body`, with the body on its own line when it doesn't
+/// fit into one.
+pub fn let_decl(name: ast::Name, params: Option<ast::ParamList>, body: ast::Expr) -> ast::LetDecl {
+    let params = match params {
+        Some(params) => format!(" {params}"),
+        None => String::new(),
+    };
+    ast_from_text(&format!("let {name}{params} ={}", rhs(body.syntax())))
+}
+
+pub fn union(name: ast::Name, cases: impl IntoIterator<Item = ast::Path>) -> ast::Union {
+    let cases = cases
+        .into_iter()
+        .map(|case| format!("\n    | {case}"))
+        .join("");
+    ast_from_text(&format!("union {name} ={cases}"))
+}
+
+pub fn module(name: ast::Name, items: impl IntoIterator<Item = ast::Item>) -> ast::Module {
+    let items = items
+        .into_iter()
+        .map(|item| format!("\n{}", indent(&reindent(item.syntax(), 0), INDENT)))
+        .join("");
+    ast_from_text(&format!("module {name} ={items}"))
+}
+
+pub fn item_from_text(text: &str) -> ast::Item {
+    ast_from_text(text)
+}
+
+pub fn expr_from_text(text: &str) -> ast::Expr {
+    let parse = ast::Expr::parse(text);
+    assert!(parse.errors().is_empty(), "invalid expression `{text}`");
+    parse.tree()
+}
+
+pub mod tokens {
+    //! Detached tokens for mutable trees.
+
+    use crate::{SourceFile, SyntaxKind, SyntaxToken, ast::AstNode};
+
+    pub fn single_space() -> SyntaxToken {
+        whitespace(" ")
+    }
+
+    /// Spaces, the only whitespace besides line breaks.
+    pub fn whitespace(text: &str) -> SyntaxToken {
+        assert!(!text.is_empty() && text.bytes().all(|it| it == b' '));
+        token_from_text(&format!("let{text}x"), SyntaxKind::WHITESPACE)
+    }
+
+    pub fn newline() -> SyntaxToken {
+        token_from_text("let x\nlet y", SyntaxKind::NEWLINE)
+    }
+
+    fn token_from_text(text: &str, kind: SyntaxKind) -> SyntaxToken {
+        let file = SourceFile::parse(text).tree();
+        let token = file
+            .syntax()
+            .clone_for_update()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|it| it.kind() == kind)
+            .unwrap();
+        token.detach();
+        token
+    }
+}
+
+/// The text of the right-hand side of `=` or `->`: on the same line if it
+/// fits into one, on the next line, indented, otherwise.
+fn rhs(node: &SyntaxNode) -> String {
+    let text = reindent(node, 0);
+    if text.contains('\n') {
+        format!("\n{}", indent(&text, INDENT))
+    } else {
+        format!(" {text}")
+    }
+}
+
+/// Parses `text` as a file, and returns the first `N` in it as a root.
+#[track_caller]
+fn ast_from_text<N: AstNode>(text: &str) -> N {
+    let parse = SourceFile::parse(text);
+    assert!(parse.errors().is_empty(), "invalid template `{text}`");
+    let node = match parse.tree().syntax().descendants().find_map(N::cast) {
+        Some(it) => it,
+        None => {
+            let node = std::any::type_name::<N>();
+            panic!("Failed to make ast node `{node}` from text `{text}`")
+        },
+    };
+    node.clone_subtree()
+}
+
+/// The text of `node` with its lines after the first one moved so that they
+/// are indented by `level` relative to the line where the node starts.
+fn reindent(node: &SyntaxNode, level: usize) -> String {
+    let base = IndentLevel::from_node(node).0;
+    let text = node.to_string();
+    let mut lines = text.split('\n');
+    let mut res = lines.next().unwrap_or_default().to_owned();
+    for line in lines {
+        res.push('\n');
+        if line.trim().is_empty() {
+            continue;
+        }
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        res.extend(std::iter::repeat_n(
+            ' ',
+            level + spaces.saturating_sub(base),
+        ));
+        res.push_str(&line[spaces..]);
+    }
+    res
+}
+
+/// The text of `node` placed at `column`, in parentheses if `parens`.
+fn operand(node: &SyntaxNode, parens: bool, column: usize) -> String {
+    match parens {
+        true => format!("({})", reindent(node, column + 1)),
+        false => reindent(node, column),
+    }
+}
+
+fn last_line_len(text: &str) -> usize {
+    text.rsplit('\n').next().unwrap_or_default().len()
+}
+
+/// Indents the non-blank lines of `text` by `level` spaces.
+fn indent(text: &str, level: usize) -> String {
+    let prefix = " ".repeat(level);
+    text.split('\n')
+        .map(|line| {
+            match line.trim().is_empty() {
+                true => String::new(),
+                false => format!("{prefix}{line}"),
+            }
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that `node` is exactly what the parser makes of its text, either
+    /// as a file or as an expression.
+    #[track_caller]
+    fn check_round_trip<N: AstNode>(node: &N, expected: &str) {
+        let text = node.syntax().to_string();
+        assert_eq!(text, expected);
+        let file = SourceFile::parse(&text);
+        let root = match file.errors().is_empty() {
+            true => file.syntax_node(),
+            false => expr_from_text(&text).syntax().clone(),
+        };
+        let reparsed = root.descendants().find_map(N::cast).unwrap();
+        assert_eq!(reparsed.syntax().text_range(), root.text_range());
+        assert_eq!(
+            format!("{:#?}", reparsed.syntax()),
+            format!("{:#?}", node.syntax())
+        );
+    }
+
+    #[test]
+    fn make_let_decl() {
+        let params = param_list([
+            ast::Pat::IdentPat(ident_pat(name("x"))),
+            ast::Pat::parse("y :: ys").ok().unwrap(),
+        ]);
+        let body = expr_bin(
+            expr_path(path_from_text("x")),
+            BinaryOp::Add,
+            expr_path(path_from_text("y")),
+        );
+        let decl = let_decl(name("f"), Some(params), body);
+        check_round_trip(&decl, "let f x (y :: ys) = x + y");
+
+        let local = let_decl(name("y"), None, expr_literal("1").into());
+        let expr = expr_paren(expr_let(local, expr_path(path_from_text("y"))));
+        check_round_trip(&expr, "(let y = 1 in y)");
+
+        let local = let_decl(name("y"), None, expr_literal("1").into());
+        let body = expr_from_text("match y with\n| A -> 1");
+        let expr = expr_paren(expr_let(local, body));
+        check_round_trip(&expr, "(let y = 1 in\n match y with\n | A -> 1)");
+    }
+
+    /// Checks that `expr` reparses as `lhs op rhs`.
+    #[track_caller]
+    fn check_bin(expr: &ast::Expr, expected: &str, op: BinaryOp, lhs: &str, rhs: &str) {
+        check_round_trip(expr, expected);
+        let ast::Expr::BinExpr(bin) = expr_from_text(expected) else {
+            panic!("`{expected}` is not a binary expression");
+        };
+        assert_eq!(bin.op_kind(), Some(op));
+        assert_eq!(bin.lhs().unwrap().to_string(), lhs);
+        assert_eq!(bin.rhs().unwrap().to_string(), rhs);
+    }
+
+    /// Checks that `expr` reparses as `fun arg`.
+    #[track_caller]
+    fn check_app(expr: &ast::Expr, expected: &str, fun: &str, arg: &str) {
+        check_round_trip(expr, expected);
+        let ast::Expr::AppExpr(app) = expr_from_text(expected) else {
+            panic!("`{expected}` is not an application");
+        };
+        assert_eq!(app.fun().unwrap().to_string(), fun);
+        assert_eq!(app.arg().unwrap().to_string(), arg);
+    }
+
+    #[test]
+    fn make_bin_expr() {
+        let e = expr_from_text;
+        let bin = expr_bin(e("a + b"), BinaryOp::Mul, e("c"));
+        check_bin(&bin, "(a + b) * c", BinaryOp::Mul, "(a + b)", "c");
+        let bin = expr_bin(e("a * b"), BinaryOp::Add, e("c * d"));
+        check_bin(&bin, "a * b + c * d", BinaryOp::Add, "a * b", "c * d");
+        let bin = expr_bin(e("a - b"), BinaryOp::Sub, e("c"));
+        check_bin(&bin, "a - b - c", BinaryOp::Sub, "a - b", "c");
+        let bin = expr_bin(e("a"), BinaryOp::Sub, e("b - c"));
+        check_bin(&bin, "a - (b - c)", BinaryOp::Sub, "a", "(b - c)");
+        let bin = expr_bin(e("x"), BinaryOp::Cons, e("y :: z"));
+        check_bin(&bin, "x :: y :: z", BinaryOp::Cons, "x", "y :: z");
+        let bin = expr_bin(e("x :: y"), BinaryOp::Cons, e("z"));
+        check_bin(&bin, "(x :: y) :: z", BinaryOp::Cons, "(x :: y)", "z");
+        let bin = expr_bin(e("f x"), BinaryOp::Eq, e("-y"));
+        check_bin(&bin, "f x = -y", BinaryOp::Eq, "f x", "-y");
+
+        // `if` and `match` would take the rest into their last branch.
+        let bin = expr_bin(e("a + if c then x else y"), BinaryOp::Or, e("d"));
+        check_bin(
+            &bin,
+            "(a + if c then x else y) || d",
+            BinaryOp::Or,
+            "(a + if c then x else y)",
+            "d",
+        );
+        let bin = expr_bin(e("a"), BinaryOp::Add, e("match x with\n| A -> 1\n| B -> 2"));
+        check_bin(
+            &bin,
+            "a + (match x with\n     | A -> 1\n     | B -> 2)",
+            BinaryOp::Add,
+            "a",
+            "(match x with\n     | A -> 1\n     | B -> 2)",
+        );
+    }
+
+    #[test]
+    fn make_app_expr() {
+        let e = expr_from_text;
+        let app = expr_app(e("f"), e("g x"));
+        check_app(&app, "f (g x)", "f", "(g x)");
+        let app = expr_app(e("f x"), e("y"));
+        check_app(&app, "f x y", "f x", "y");
+        let app = expr_app(e("f"), e("-1"));
+        check_app(&app, "f (-1)", "f", "(-1)");
+        let app = expr_app(e("-f"), e("x"));
+        check_app(&app, "(-f) x", "(-f)", "x");
+        let app = expr_app(e("f"), e("a, b"));
+        check_app(&app, "f (a, b)", "f", "(a, b)");
+        let app = expr_app(e("Std.f x"), e("match x with\n| A -> 1"));
+        check_app(
+            &app,
+            "Std.f x (match x with\n         | A -> 1)",
+            "Std.f x",
+            "(match x with\n         | A -> 1)",
+        );
+    }
+
+    #[test]
+    fn make_match_expr() {
+        let case = |pat: &str, expr: &str| {
+            match_case(
+                ast::Pat::parse(pat).ok().unwrap(),
+                None,
+                expr_from_text(expr),
+            )
+        };
+        let inner = match_expr(expr_path(path_from_text("y")), [
+            case("A", "1"),
+            case("_", "2"),
+        ]);
+        let outer = match_expr(expr_path(path_from_text("x")), [
+            case("Some(y)", &inner.to_string()),
+            match_case(
+                ast::Pat::WildcardPat(wildcard_pat()),
+                Some(expr_from_text("debug")),
+                expr_literal("0").into(),
+            ),
+        ]);
+        check_round_trip(
+            &outer,
+            "\
+match x with
+| Some(y) ->
+    match y with
+    | A -> 1
+    | _ -> 2
+| _ when debug -> 0",
+        );
+
+        // Multi-line scrutinees and guards stay to the right of `match` and
+        // `when`.
+        let nested = expr_app(
+            expr_path(path_from_text("f")),
+            expr_from_text("match y with\n| A -> 1"),
+        );
+        let expr = match_expr(nested.clone(), [match_case(
+            ast::Pat::WildcardPat(wildcard_pat()),
+            Some(nested),
+            expr_literal("0").into(),
+        )]);
+        check_round_trip(
+            &expr,
+            "\
+match f (match y with
+         | A -> 1) with
+| _ when f (match y with
+            | A -> 1) -> 0",
+        );
+
+        let decl = let_decl(name("f"), None, outer.into());
+        check_round_trip(
+            &decl,
+            "\
+let f =
+    match x with
+    | Some(y) ->
+        match y with
+        | A -> 1
+        | _ -> 2
+    | _ when debug -> 0",
+        );
+    }
+
+    #[test]
+    fn make_union_and_module() {
+        let option = union(name("Option"), [
+            path_from_text("Some[a]"),
+            path_from_text("None"),
+        ]);
+        check_round_trip(&option, "union Option =\n    | Some[a]\n    | None");
+
+        let segments = ["Std", "List", "map"].map(|it| path_segment(name(it)));
+        check_round_trip(&path(segments), "Std.List.map");
+
+        let file = SourceFile::parse("module A =\n    let f =\n        g\n").tree();
+        let f = file
+            .syntax()
+            .descendants()
+            .find_map(ast::LetDecl::cast)
+            .unwrap();
+        let m = module(name("M"), [
+            ast::Item::LetDecl(f),
+            item_from_text("import Std"),
+        ]);
+        check_round_trip(&m, "module M =\n    let f =\n        g\n    import Std");
+    }
+}
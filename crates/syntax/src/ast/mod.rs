@@ -1,10 +1,25 @@
+mod documentation;
+pub mod edit;
+mod expr_ext;
 mod generated;
+pub mod make;
+mod node_ext;
+mod operators;
+mod token_ext;
+mod traits;
 
 use std::marker::PhantomData;
 
 use either::Either;
 
-pub use self::generated::{nodes::*, tokens::*};
+pub use self::{
+    documentation::Documentation,
+    expr_ext::LiteralKind,
+    generated::{nodes::*, tokens::*},
+    operators::{Associativity, BinaryOp},
+    token_ext::{DocPlacement, FStringPart, INT_SUFFIXES, LiteralError, LiteralErrorKind},
+    traits::{HasAttrs, HasConstraintList, HasDocComments, HasName, HasParamList, HasTypeVars},
+};
 use crate::{
     SyntaxKind,
     syntax_node::{SyntaxNode, SyntaxNodeChildren, SyntaxToken},
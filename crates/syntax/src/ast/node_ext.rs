@@ -0,0 +1,90 @@
+//! Various extension methods to ast nodes that are hard to code-generate.
+//! Extensions for expressions live in `expr_ext`.
+
+use crate::ast::{self, AstNode, HasName, support};
+
+impl ast::ConsPat {
+    pub fn head(&self) -> Option<ast::Pat> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn rest(&self) -> Option<ast::Pat> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::FnType {
+    pub fn arg(&self) -> Option<ast::Type> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn ret(&self) -> Option<ast::Type> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::Path {
+    /// The segments of the path, from the first one.
+    pub fn segments(&self) -> impl Iterator<Item = ast::PathSegment> {
+        let mut segments: Vec<_> = std::iter::successors(Some(self.clone()), ast::Path::qualifier)
+            .filter_map(|it| it.segment())
+            .collect();
+        segments.reverse();
+        segments.into_iter()
+    }
+}
+
+/// The name of a `let` is its head when the head is a single name, as `f` in
+/// `let f x = ...`. A `let` of an operator, found with
+/// [`ast::LetDecl::let_op`], or of a pattern such as `a, b` has none.
+impl HasName for ast::LetDecl {
+    fn name(&self) -> Option<ast::Name> {
+        match self.pat()? {
+            ast::Pat::IdentPat(pat) if pat.pat().is_none() => pat.name(),
+            _ => None,
+        }
+    }
+}
+
+impl HasName for ast::AnyHasName {
+    fn name(&self) -> Option<ast::Name> {
+        match ast::LetDecl::cast(self.syntax().clone()) {
+            Some(decl) => decl.name(),
+            None => support::child(self.syntax()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceFile;
+
+    #[test]
+    fn positional_children() {
+        let file = SourceFile::parse("let x :: xs : int -> int -> int = l").tree();
+        let cons = file
+            .syntax()
+            .descendants()
+            .find_map(ast::ConsPat::cast)
+            .unwrap();
+        assert_eq!(cons.head().unwrap().syntax().text(), "x");
+        assert_eq!(cons.rest().unwrap().syntax().text(), "xs");
+
+        let fn_type = file
+            .syntax()
+            .descendants()
+            .find_map(ast::FnType::cast)
+            .unwrap();
+        assert_eq!(fn_type.arg().unwrap().syntax().text(), "int");
+        assert_eq!(fn_type.ret().unwrap().syntax().text(), "int -> int");
+    }
+
+    #[test]
+    fn path_segments() {
+        let file = SourceFile::parse("module Std.Collections.List").tree();
+        let path = file.preamble().unwrap().path().unwrap();
+        let segments: Vec<_> = path.segments().map(|it| it.syntax().to_string()).collect();
+        assert_eq!(segments, ["Std", "Collections", "List"]);
+    }
+}
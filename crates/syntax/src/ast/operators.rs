@@ -0,0 +1,114 @@
+//! Data-less enums for operators, so that tools can reason about operators
+//! without holding onto the syntax.
+
+use std::fmt;
+
+use crate::{SyntaxKind, T};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    /// `||`
+    Or,
+    /// `&&`
+    And,
+    /// `=`
+    Eq,
+    /// `<>`
+    Neq,
+    /// `<`
+    Lt,
+    /// `>`
+    Gt,
+    /// `<=`
+    Le,
+    /// `>=`
+    Ge,
+    /// `::`
+    Cons,
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+impl BinaryOp {
+    pub fn from_kind(kind: SyntaxKind) -> Option<BinaryOp> {
+        let op = match kind {
+            T![||] => BinaryOp::Or,
+            T![&&] => BinaryOp::And,
+            T![=] => BinaryOp::Eq,
+            T![<>] => BinaryOp::Neq,
+            T![<] => BinaryOp::Lt,
+            T![>] => BinaryOp::Gt,
+            T![<=] => BinaryOp::Le,
+            T![>=] => BinaryOp::Ge,
+            T![::] => BinaryOp::Cons,
+            T![+] => BinaryOp::Add,
+            T![-] => BinaryOp::Sub,
+            T![*] => BinaryOp::Mul,
+            T![/] => BinaryOp::Div,
+            T![%] => BinaryOp::Rem,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// How tightly the operator binds, from `1` for `||` to `6` for `*`. The
+    /// same as in the parser.
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq
+            | BinaryOp::Neq
+            | BinaryOp::Lt
+            | BinaryOp::Gt
+            | BinaryOp::Le
+            | BinaryOp::Ge => 3,
+            BinaryOp::Cons => 4,
+            BinaryOp::Add | BinaryOp::Sub => 5,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 6,
+        }
+    }
+
+    pub fn associativity(self) -> Associativity {
+        match self {
+            BinaryOp::Cons => Associativity::Right,
+            _ => Associativity::Left,
+        }
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let res = match self {
+            BinaryOp::Or => "||",
+            BinaryOp::And => "&&",
+            BinaryOp::Eq => "=",
+            BinaryOp::Neq => "<>",
+            BinaryOp::Lt => "<",
+            BinaryOp::Gt => ">",
+            BinaryOp::Le => "<=",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Cons => "::",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+        };
+        f.write_str(res)
+    }
+}
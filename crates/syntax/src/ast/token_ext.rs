@@ -0,0 +1,330 @@
+//! There are many AstNodes, but only a few tokens, so we hand-write them here.
+
+use std::fmt;
+
+use crate::{
+    TextRange, TextSize,
+    ast::{self, AstToken},
+};
+
+/// Something wrong with the value of a literal, at the part of the source
+/// text that causes it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LiteralError {
+    pub kind: LiteralErrorKind,
+    pub range: TextRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LiteralErrorKind {
+    /// An integer that doesn't fit into 128 bits.
+    IntOverflow,
+    /// A suffix of an integer that is not in [`INT_SUFFIXES`], as `foo` in
+    /// `92foo`.
+    InvalidSuffix,
+    /// A float that doesn't fit into 64 bits.
+    FloatOverflow,
+    /// A backslash followed by something that can't be escaped.
+    UnknownEscape,
+    /// A char literal with no or several chars in it.
+    NotOneChar,
+    /// A `{}` without an expression in an f-string.
+    EmptyHole,
+    /// A `{` without the closing `}` in an f-string.
+    UnclosedHole,
+    /// A `}` that doesn't close a hole in an f-string.
+    UnmatchedBrace,
+}
+
+impl fmt::Display for LiteralErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LiteralErrorKind::IntOverflow => "integer literal is too large",
+            LiteralErrorKind::InvalidSuffix => "invalid suffix for an integer literal",
+            LiteralErrorKind::FloatOverflow => "float literal is too large",
+            LiteralErrorKind::UnknownEscape => "unknown character escape",
+            LiteralErrorKind::NotOneChar => "a char literal must contain exactly one char",
+            LiteralErrorKind::EmptyHole => "empty hole in f-string",
+            LiteralErrorKind::UnclosedHole => "unclosed hole in f-string",
+            LiteralErrorKind::UnmatchedBrace => "unmatched `}` in f-string",
+        })
+    }
+}
+
+/// The suffixes of integer literals, which give the type of the integer, as
+/// `u8` in `92u8`.
+pub const INT_SUFFIXES: [&str; 10] = [
+    "i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128",
+];
+
+/// A piece of an f-string: either text, or an expression in `{}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FStringPart {
+    /// Unescaped text.
+    Text(String),
+    /// The source text of the expression between the braces, and its range.
+    Hole { text: String, range: TextRange },
+}
+
+/// What a doc comment documents: the item after it, or the module or file
+/// that it is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DocPlacement {
+    /// `///` and `/** */`.
+    Outer,
+    /// `//!` and `/*! */`.
+    Inner,
+}
+
+impl ast::Comment {
+    /// The placement of a doc comment, or `None` if this is a plain comment.
+    /// `////` and `/***` start plain comments, as in lines of slashes.
+    pub fn doc_placement(&self) -> Option<DocPlacement> {
+        let text = self.text();
+        if text.starts_with("////") || text.starts_with("/***") || text == "/**/" {
+            return None;
+        }
+        match text.get(..3)? {
+            "///" | "/**" => Some(DocPlacement::Outer),
+            "//!" | "/*!" => Some(DocPlacement::Inner),
+            _ => None,
+        }
+    }
+
+    pub fn is_doc(&self) -> bool {
+        self.doc_placement().is_some()
+    }
+
+    /// Whether this is a `/* */` comment, as opposed to a `//` one.
+    pub fn is_block(&self) -> bool {
+        self.text().starts_with("/*")
+    }
+}
+
+impl ast::IntNumber {
+    /// Splits `92u8` into `("92", "u8")`.
+    pub fn split_into_parts(&self) -> (&str, &str) {
+        let text = self.text();
+        let suffix_start = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        text.split_at(suffix_start)
+    }
+
+    pub fn suffix(&self) -> Option<&str> {
+        let (_, suffix) = self.split_into_parts();
+        (!suffix.is_empty()).then_some(suffix)
+    }
+
+    pub fn value(&self) -> Result<u128, LiteralError> {
+        let (digits, _) = self.split_into_parts();
+        let overflow = || {
+            LiteralError {
+                kind: LiteralErrorKind::IntOverflow,
+                range: TextRange::at(self.syntax().text_range().start(), TextSize::of(digits)),
+            }
+        };
+        let value = digits.bytes().try_fold(0u128, |acc, digit| {
+            acc.checked_mul(10)
+                .and_then(|acc| acc.checked_add(u128::from(digit - b'0')))
+                .ok_or_else(overflow)
+        })?;
+        match self.suffix_error() {
+            Some(error) => Err(error),
+            None => Ok(value),
+        }
+    }
+
+    /// An error at the suffix if it is not in [`INT_SUFFIXES`].
+    fn suffix_error(&self) -> Option<LiteralError> {
+        let (digits, suffix) = self.split_into_parts();
+        if suffix.is_empty() || INT_SUFFIXES.contains(&suffix) {
+            return None;
+        }
+        let start = self.syntax().text_range().start() + TextSize::of(digits);
+        Some(LiteralError {
+            kind: LiteralErrorKind::InvalidSuffix,
+            range: TextRange::at(start, TextSize::of(suffix)),
+        })
+    }
+
+    pub(crate) fn errors(&self) -> Vec<LiteralError> {
+        let mut res: Vec<_> = self.value().err().into_iter().collect();
+        res.extend(self.suffix_error().filter(|it| !res.contains(it)));
+        res
+    }
+}
+
+impl ast::FloatNumber {
+    pub fn value(&self) -> Result<f64, LiteralError> {
+        // The lexer only produces well-formed floats, so the parsing may only
+        // fail on trees made by hand.
+        match self.text().parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => {
+                Err(LiteralError {
+                    kind: LiteralErrorKind::FloatOverflow,
+                    range: self.syntax().text_range(),
+                })
+            },
+        }
+    }
+}
+
+impl ast::String {
+    /// The text with the escapes resolved.
+    pub fn value(&self) -> Result<String, LiteralError> {
+        let (parts, errors) = scan(self.syntax(), 1, false);
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+        Ok(parts
+            .into_iter()
+            .map(|part| {
+                match part {
+                    FStringPart::Text(text) => text,
+                    FStringPart::Hole { .. } => unreachable!(),
+                }
+            })
+            .collect())
+    }
+
+    pub(crate) fn errors(&self) -> Vec<LiteralError> {
+        scan(self.syntax(), 1, false).1
+    }
+}
+
+impl ast::FString {
+    /// The text and the holes, in order, with the escapes resolved.
+    pub fn parts(&self) -> Result<Vec<FStringPart>, LiteralError> {
+        let (parts, errors) = scan(self.syntax(), 2, true);
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(parts),
+        }
+    }
+
+    pub(crate) fn errors(&self) -> Vec<LiteralError> {
+        scan(self.syntax(), 2, true).1
+    }
+}
+
+impl ast::Char {
+    pub fn value(&self) -> Result<char, LiteralError> {
+        let (parts, errors) = scan(self.syntax(), 1, false);
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+        let text = match parts.as_slice() {
+            [FStringPart::Text(text)] => text.as_str(),
+            _ => "",
+        };
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => {
+                Err(LiteralError {
+                    kind: LiteralErrorKind::NotOneChar,
+                    range: self.syntax().text_range(),
+                })
+            },
+        }
+    }
+}
+
+/// Splits the contents of a quoted literal into parts, resolving escapes.
+///
+/// `prefix` is the length of the opening quote, holes are recognized only in
+/// f-strings, where `\{` and `\}` stand for the braces themselves.
+fn scan(
+    token: &crate::SyntaxToken,
+    prefix: usize,
+    fstring: bool,
+) -> (Vec<FStringPart>, Vec<LiteralError>) {
+    let text = token.text();
+    let contents = text.get(prefix..).unwrap_or_default();
+    let contents = contents.strip_suffix(['"', '\'']).unwrap_or(contents);
+    let start = token.text_range().start() + TextSize::of(text.get(..prefix).unwrap_or(text));
+    let range = |from: usize, to: usize| {
+        TextRange::new(
+            start + TextSize::of(&contents[..from]),
+            start + TextSize::of(&contents[..to]),
+        )
+    };
+
+    let mut parts = Vec::new();
+    let mut errors = Vec::new();
+    let mut buf = String::new();
+    let mut chars = contents.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                let Some((j, escaped)) = chars.next() else {
+                    errors.push(LiteralError {
+                        kind: LiteralErrorKind::UnknownEscape,
+                        range: range(i, i + 1),
+                    });
+                    break;
+                };
+                match unescape(escaped, fstring) {
+                    Some(c) => buf.push(c),
+                    None => {
+                        errors.push(LiteralError {
+                            kind: LiteralErrorKind::UnknownEscape,
+                            range: range(i, j + escaped.len_utf8()),
+                        })
+                    },
+                }
+            },
+            '{' if fstring => {
+                let Some(close) = contents[i..].find('}').map(|it| i + it) else {
+                    errors.push(LiteralError {
+                        kind: LiteralErrorKind::UnclosedHole,
+                        range: range(i, contents.len()),
+                    });
+                    break;
+                };
+                let hole = &contents[i + 1..close];
+                if hole.trim().is_empty() {
+                    errors.push(LiteralError {
+                        kind: LiteralErrorKind::EmptyHole,
+                        range: range(i, close + 1),
+                    });
+                } else {
+                    if !buf.is_empty() {
+                        parts.push(FStringPart::Text(std::mem::take(&mut buf)));
+                    }
+                    parts.push(FStringPart::Hole {
+                        text: hole.to_owned(),
+                        range: range(i + 1, close),
+                    });
+                }
+                while chars.next_if(|&(j, _)| j <= close).is_some() {}
+            },
+            '}' if fstring => {
+                errors.push(LiteralError {
+                    kind: LiteralErrorKind::UnmatchedBrace,
+                    range: range(i, i + 1),
+                })
+            },
+            c => buf.push(c),
+        }
+    }
+    if !buf.is_empty() || parts.is_empty() {
+        parts.push(FStringPart::Text(buf));
+    }
+    (parts, errors)
+}
+
+fn unescape(c: char, fstring: bool) -> Option<char> {
+    let res = match c {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        '\\' | '\'' | '"' => c,
+        '{' | '}' if fstring => c,
+        _ => return None,
+    };
+    Some(res)
+}
@@ -0,0 +1,118 @@
+//! Various traits that are implemented by ast nodes.
+//!
+//! The implementations are usually trivial, and live in generated code.
+
+use crate::ast::{self, AstChildren, AstNode, documentation, support};
+
+pub trait HasName: AstNode {
+    fn name(&self) -> Option<ast::Name> {
+        support::child(self.syntax())
+    }
+}
+
+pub trait HasAttrs: AstNode {
+    fn attrs(&self) -> AstChildren<ast::Attr> {
+        support::children(self.syntax())
+    }
+}
+
+pub trait HasConstraintList: AstNode {
+    fn constraint_list(&self) -> Option<ast::ConstraintList> {
+        support::child(self.syntax())
+    }
+}
+
+pub trait HasTypeVars: AstNode {
+    fn type_vars(&self) -> AstChildren<ast::TypeVar> {
+        support::children(self.syntax())
+    }
+}
+
+pub trait HasParamList: AstNode {
+    fn param_list(&self) -> Option<ast::ParamList> {
+        support::child(self.syntax())
+    }
+}
+
+pub trait HasDocComments: AstNode {
+    /// The Markdown of the doc comments that the parser attached to the node,
+    /// followed by the inner ones of modules.
+    fn doc_comments(&self) -> Option<ast::Documentation> {
+        ast::Documentation::from_comments(documentation::doc_comments(self.syntax()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        SourceFile,
+        SyntaxKind::{self, *},
+    };
+
+    #[test]
+    fn items_uniformly() {
+        let text = "\
+/// The answer.
+[<Inline>]
+let answer = 42
+let (+) a b = a
+let a, b = p
+import Std.List
+attribute Inline
+module M =
+    class Eq a =
+        let eq
+union Option [Eq a] = Some | None
+and value Point (x : int * y : int)
+and alias Num = int
+where IntEq Eq[int] =
+    let eq a b = a = b
+";
+        let file = SourceFile::parse(text).tree();
+        let names: Vec<(SyntaxKind, Option<String>)> = file
+            .syntax()
+            .descendants()
+            .filter_map(ast::AnyHasAttrs::cast)
+            .map(|it| {
+                let name = ast::AnyHasName::cast(it.syntax().clone())
+                    .and_then(|it| it.name())
+                    .map(|it| it.syntax().text().to_string());
+                (it.syntax().kind(), name)
+            })
+            .collect();
+        let name = |kind, name: &str| (kind, Some(name.to_owned()));
+        assert_eq!(names, [
+            name(LET_DECL, "answer"),
+            (LET_DECL, None),
+            (LET_DECL, None),
+            (IMPORT, None),
+            name(ATTR_DEF, "Inline"),
+            name(MODULE, "M"),
+            name(CLASS, "Eq"),
+            name(LET_DECL, "eq"),
+            name(UNION, "Option"),
+            name(VALUE, "Point"),
+            name(ALIAS, "Num"),
+            name(WHERE, "IntEq"),
+            name(LET_DECL, "eq"),
+        ]);
+
+        let let_decl = file
+            .syntax()
+            .descendants()
+            .find_map(ast::LetDecl::cast)
+            .unwrap();
+        let docs = let_decl.doc_comments().unwrap();
+        assert_eq!(docs.text(), "The answer.");
+        assert_eq!(let_decl.attrs().count(), 1);
+        assert!(let_decl.param_list().is_none());
+
+        let union = file
+            .syntax()
+            .descendants()
+            .find_map(ast::Union::cast)
+            .unwrap();
+        assert!(union.constraint_list().is_some());
+    }
+}
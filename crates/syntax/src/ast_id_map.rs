@@ -0,0 +1,250 @@
+//! `AstIdMap` allows to create stable ids for items in a file.
+//!
+//! Specifically, it enumerates all items in a file and uses the kind, the name
+//! and the enclosing item of each one to come up with its id. That way, the id
+//! of an item doesn't change when other items are edited, added or removed, so
+//! later phases can refer to items by id and not recompute everything after
+//! each keystroke.
+
+use std::{
+    any::type_name,
+    fmt,
+    hash::{BuildHasher, Hash, Hasher},
+    marker::PhantomData,
+};
+
+use rustc_hash::{FxBuildHasher, FxHashMap};
+
+use crate::{
+    AstNode, AstPtr, SyntaxKind, SyntaxNode, SyntaxNodePtr,
+    ast::{self, HasName},
+};
+
+/// An item that gets an id in an [`AstIdMap`].
+pub trait AstIdNode: AstNode {}
+
+macro_rules! register_ast_id_node {
+    ($($node:ident),* $(,)?) => {
+        $(impl AstIdNode for ast::$node {})*
+
+        fn should_alloc_id(kind: SyntaxKind) -> bool {
+            $(ast::$node::can_cast(kind))||*
+        }
+    };
+}
+register_ast_id_node! {
+    Item, Module, LetDecl, Import, AttrDef, Class, AdtList, Adt, Union, Value, Alias, Where,
+}
+
+/// The id of an item, erased of its type.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ErasedFileAstId(u32);
+
+impl ErasedFileAstId {
+    pub fn into_raw(self) -> u32 {
+        self.0
+    }
+
+    pub fn from_raw(raw: u32) -> ErasedFileAstId {
+        ErasedFileAstId(raw)
+    }
+}
+
+/// The id of an item of type `N`, local to a file.
+pub struct FileAstId<N: AstIdNode> {
+    raw: ErasedFileAstId,
+    _ty: PhantomData<fn() -> N>,
+}
+
+impl<N: AstIdNode> Clone for FileAstId<N> {
+    fn clone(&self) -> FileAstId<N> {
+        *self
+    }
+}
+impl<N: AstIdNode> Copy for FileAstId<N> {}
+
+impl<N: AstIdNode> PartialEq for FileAstId<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+impl<N: AstIdNode> Eq for FileAstId<N> {}
+impl<N: AstIdNode> Hash for FileAstId<N> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.hash(hasher);
+    }
+}
+
+impl<N: AstIdNode> fmt::Debug for FileAstId<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FileAstId::<{}>({})", type_name::<N>(), self.raw.0)
+    }
+}
+
+impl<N: AstIdNode> FileAstId<N> {
+    pub fn upcast<M: AstIdNode>(self) -> FileAstId<M>
+    where
+        N: Into<M>,
+    {
+        FileAstId {
+            raw: self.raw,
+            _ty: PhantomData,
+        }
+    }
+
+    pub fn erase(self) -> ErasedFileAstId {
+        self.raw
+    }
+}
+
+/// Maps items of a file to their ids and back.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct AstIdMap {
+    ptrs: FxHashMap<ErasedFileAstId, SyntaxNodePtr>,
+    ids: FxHashMap<SyntaxNodePtr, ErasedFileAstId>,
+}
+
+impl AstIdMap {
+    pub fn from_source(root: &SyntaxNode) -> AstIdMap {
+        assert!(root.parent().is_none());
+        let mut res = AstIdMap::default();
+        // How many items with the same key came before, to tell apart items
+        // with the same name, as shadowed `let`s.
+        let mut seen: FxHashMap<u64, u32> = FxHashMap::default();
+        for node in root.descendants().filter(|it| should_alloc_id(it.kind())) {
+            if is_local(&node) {
+                continue;
+            }
+            // Ancestors come first in the preorder, so they have their ids.
+            let parent = node
+                .ancestors()
+                .skip(1)
+                .find_map(|it| res.ids.get(&SyntaxNodePtr::new(&it)).copied());
+            let key = FxBuildHasher.hash_one((node.kind(), item_name(&node), parent));
+            let nth = seen.entry(key).or_default();
+            let mut raw = FxBuildHasher.hash_one((key, *nth)) as u32;
+            *nth += 1;
+            // On the rare collision of hashes, the order of the items decides.
+            while res.ptrs.contains_key(&ErasedFileAstId(raw)) {
+                raw = raw.wrapping_add(1);
+            }
+            let ptr = SyntaxNodePtr::new(&node);
+            res.ptrs.insert(ErasedFileAstId(raw), ptr);
+            res.ids.insert(ptr, ErasedFileAstId(raw));
+        }
+        res
+    }
+
+    /// The id of `item`, which must be in the tree that the map was made from.
+    pub fn ast_id<N: AstIdNode>(&self, item: &N) -> FileAstId<N> {
+        let raw = self.erased_ast_id(item.syntax());
+        FileAstId {
+            raw,
+            _ty: PhantomData,
+        }
+    }
+
+    pub fn get<N: AstIdNode>(&self, id: FileAstId<N>) -> AstPtr<N> {
+        AstPtr::try_from_raw(self.get_erased(id.raw)).unwrap()
+    }
+
+    pub fn get_erased(&self, id: ErasedFileAstId) -> SyntaxNodePtr {
+        self.ptrs[&id]
+    }
+
+    fn erased_ast_id(&self, item: &SyntaxNode) -> ErasedFileAstId {
+        let ptr = SyntaxNodePtr::new(item);
+        match self.ids.get(&ptr) {
+            Some(&id) => id,
+            None => {
+                panic!(
+                    "Can't find {:?} in AstIdMap:\n{:?}",
+                    item,
+                    self.ptrs.values().collect::<Vec<_>>(),
+                )
+            },
+        }
+    }
+}
+
+/// `let`s in expressions are not items.
+fn is_local(node: &SyntaxNode) -> bool {
+    node.kind() == SyntaxKind::LET_DECL
+        && node
+            .parent()
+            .is_some_and(|it| it.kind() == SyntaxKind::LET_EXPR)
+}
+
+/// What tells the item apart from the other items of the same kind.
+fn item_name(node: &SyntaxNode) -> String {
+    if let Some(decl) = ast::LetDecl::cast(node.clone()) {
+        let name = match (decl.let_op(), decl.pat()) {
+            (Some(op), _) => Some(op.syntax().to_string()),
+            (None, pat) => pat.map(|it| it.syntax().to_string()),
+        };
+        return name.unwrap_or_default();
+    }
+    if let Some(import) = ast::Import::cast(node.clone()) {
+        return import.path().map(|it| it.to_string()).unwrap_or_default();
+    }
+    if let Some(item) = ast::AnyHasName::cast(node.clone()) {
+        return item.name().map(|it| it.to_string()).unwrap_or_default();
+    }
+    // An `AdtList` is known by the first of its types.
+    node.descendants()
+        .find_map(ast::Name::cast)
+        .map(|it| it.to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Indel, SourceFile, TextSize};
+
+    fn ids(text: &str) -> Vec<(String, ErasedFileAstId)> {
+        let file = SourceFile::parse(text).tree();
+        let map = AstIdMap::from_source(file.syntax());
+        file.syntax()
+            .descendants()
+            .filter_map(ast::LetDecl::cast)
+            .filter(|it| !is_local(it.syntax()))
+            .map(|it| {
+                let id = map.ast_id(&it);
+                assert_eq!(map.get(id).to_node(file.syntax()), it);
+                (it.pat().unwrap().to_string(), id.erase())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ids_survive_edits_to_other_items() {
+        let before = "let a = 1\nmodule M =\n    let b = let c = 2 in c\n    let b = 3\nlet d";
+        let edited =
+            "let a2 = 10\nlet new\nmodule M =\n    let b = let c = 2 in c\n    let b = 3\n";
+        let before = ids(before);
+        let edited = ids(edited);
+        assert_eq!(before.len(), 4);
+        // `a` is renamed, `d` is removed.
+        assert_eq!(before[1..3], edited[2..4]);
+        assert_ne!(before[1], before[2]);
+    }
+
+    #[test]
+    fn ids_after_reparse() {
+        let parse = SourceFile::parse("let f x = x\nlet g y = y");
+        let edit = Indel::insert(TextSize::from(10), " + 1".to_owned());
+        let edited = parse.reparse(&edit);
+        let id = |file: &SourceFile| {
+            let map = AstIdMap::from_source(file.syntax());
+            let g = file
+                .syntax()
+                .descendants()
+                .filter_map(ast::LetDecl::cast)
+                .nth(1)
+                .unwrap();
+            map.ast_id(&g)
+        };
+        assert_eq!(id(&parse.tree()), id(&edited.tree()));
+    }
+}
@@ -0,0 +1,357 @@
+//! Syntax highlighting: classifies the tokens of a tree by what they are in
+//! the code, and renders the result for terminals and for the web.
+//!
+//! Names are classified by the nodes around them, so that the `Name` of a
+//! `Union` is a type and the name of a `LetDecl` with parameters is a function.
+//! There is no name resolution here, so a name that is used rather than
+//! defined only gets the role its position suggests.
+
+use std::fmt::{self, Write};
+
+use crate::{
+    AstNode, AstToken, SyntaxKind,
+    SyntaxKind::*,
+    SyntaxNode, SyntaxToken, T, TextRange,
+    ast::{self, HasParamList},
+};
+
+/// What a token is, for the purposes of highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HlTag {
+    Keyword,
+    Operator,
+    Punctuation,
+    /// The name of a union, a value type, an alias or a class.
+    Type,
+    TypeVar,
+    /// A case of a union, where it is declared or used.
+    UnionCase,
+    Function,
+    Parameter,
+    Variable,
+    /// A field of a value type or of a record pattern.
+    Field,
+    Module,
+    Attribute,
+    IntNumber,
+    FloatNumber,
+    String,
+    FString,
+    Char,
+    Bool,
+    Comment,
+    DocComment,
+}
+
+impl HlTag {
+    /// The name of the tag, also the CSS class of the HTML output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HlTag::Keyword => "keyword",
+            HlTag::Operator => "operator",
+            HlTag::Punctuation => "punctuation",
+            HlTag::Type => "type",
+            HlTag::TypeVar => "type_var",
+            HlTag::UnionCase => "union_case",
+            HlTag::Function => "function",
+            HlTag::Parameter => "parameter",
+            HlTag::Variable => "variable",
+            HlTag::Field => "field",
+            HlTag::Module => "module",
+            HlTag::Attribute => "attribute",
+            HlTag::IntNumber => "int_number",
+            HlTag::FloatNumber => "float_number",
+            HlTag::String => "string",
+            HlTag::FString => "f_string",
+            HlTag::Char => "char",
+            HlTag::Bool => "bool",
+            HlTag::Comment => "comment",
+            HlTag::DocComment => "doc_comment",
+        }
+    }
+
+    /// The SGR parameters of the tag for ANSI terminals, if it is colored.
+    fn ansi(self) -> Option<&'static str> {
+        let code = match self {
+            HlTag::Keyword => "35",
+            HlTag::Operator => "36",
+            HlTag::Type | HlTag::UnionCase => "33",
+            HlTag::TypeVar => "33;3",
+            HlTag::Function => "34",
+            HlTag::Parameter => "3",
+            HlTag::Module => "1",
+            HlTag::Attribute => "95",
+            HlTag::IntNumber | HlTag::FloatNumber | HlTag::Bool => "96",
+            HlTag::String | HlTag::FString | HlTag::Char => "32",
+            HlTag::Comment => "90",
+            HlTag::DocComment => "90;3",
+            HlTag::Punctuation | HlTag::Variable | HlTag::Field => return None,
+        };
+        Some(code)
+    }
+}
+
+impl fmt::Display for HlTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HlRange {
+    pub range: TextRange,
+    pub tag: HlTag,
+}
+
+/// The tags of the tokens of `node`, in order. Whitespace and tokens that
+/// can't be classified, as in erroneous code, are left out.
+pub fn highlight(node: &SyntaxNode) -> Vec<HlRange> {
+    tokens(node)
+        .filter_map(|token| {
+            Some(HlRange {
+                range: token.text_range(),
+                tag: classify_token(&token)?,
+            })
+        })
+        .collect()
+}
+
+/// Renders the text of `node` with the escape codes of ANSI terminals.
+pub fn highlight_as_ansi(node: &SyntaxNode) -> String {
+    let mut buf = String::new();
+    for token in tokens(node) {
+        match classify_token(&token).and_then(HlTag::ansi) {
+            Some(code) => write!(buf, "\x1b[{code}m{}\x1b[0m", token.text()).unwrap(),
+            None => buf.push_str(token.text()),
+        }
+    }
+    buf
+}
+
+/// Renders the text of `node` as a `<pre>` block of HTML, with the styles
+/// that it needs.
+pub fn highlight_as_html(node: &SyntaxNode) -> String {
+    let mut buf = String::from(STYLE);
+    buf.push_str("<pre><code>");
+    for token in tokens(node) {
+        let text = html_escape(token.text());
+        match classify_token(&token) {
+            Some(tag) => write!(buf, "<span class=\"{tag}\">{text}</span>").unwrap(),
+            None => buf.push_str(&text),
+        }
+    }
+    buf.push_str("</code></pre>\n");
+    buf
+}
+
+const STYLE: &str = "\
+<style>
+pre             { color: #DCDCCC; background: #3F3F3F; padding: 0.4em; }
+.keyword        { color: #F0DFAF; font-weight: bold; }
+.operator       { color: #9FC8C8; }
+.type           { color: #7CB8BB; }
+.type_var       { color: #7CB8BB; font-style: italic; }
+.union_case     { color: #BFEBBF; }
+.function       { color: #93E0E3; }
+.parameter      { color: #94BFF3; }
+.field          { color: #94BFF3; }
+.module         { color: #DCDCCC; font-weight: bold; }
+.attribute      { color: #94BFF3; }
+.int_number     { color: #BFEBBF; }
+.float_number   { color: #BFEBBF; }
+.bool           { color: #BFE6EB; }
+.string         { color: #CC9393; }
+.f_string       { color: #CC9393; }
+.char           { color: #CC9393; }
+.comment        { color: #7F9F7F; }
+.doc_comment    { color: #7F9F7F; font-style: italic; }
+</style>
+";
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn tokens(node: &SyntaxNode) -> impl Iterator<Item = SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+}
+
+pub fn classify_token(token: &SyntaxToken) -> Option<HlTag> {
+    let kind = token.kind();
+    let tag = match kind {
+        COMMENT => {
+            match ast::Comment::cast(token.clone())?.is_doc() {
+                true => HlTag::DocComment,
+                false => HlTag::Comment,
+            }
+        },
+        ATTRIBUTE => HlTag::Attribute,
+        INT_NUMBER => HlTag::IntNumber,
+        FLOAT_NUMBER => HlTag::FloatNumber,
+        STRING => HlTag::String,
+        F_STRING => HlTag::FString,
+        CHAR => HlTag::Char,
+        T![true] | T![false] => HlTag::Bool,
+        IDENT => return classify_ident(token),
+        _ if kind.is_keyword() => HlTag::Keyword,
+        _ if is_operator(token) => HlTag::Operator,
+        _ if kind.is_punct() => HlTag::Punctuation,
+        _ => return None,
+    };
+    Some(tag)
+}
+
+fn is_operator(token: &SyntaxToken) -> bool {
+    let Some(parent) = token.parent() else {
+        return false;
+    };
+    matches!(
+        (token.kind(), parent.kind()),
+        (_, OPERATOR) | (T![->], FN_TYPE) | (T![::], CONS_PAT)
+    )
+}
+
+fn classify_ident(token: &SyntaxToken) -> Option<HlTag> {
+    let parent = token.parent()?;
+    match parent.kind() {
+        TYPE_VAR => Some(HlTag::TypeVar),
+        NAME => classify_name(&parent),
+        _ => None,
+    }
+}
+
+fn classify_name(name: &SyntaxNode) -> Option<HlTag> {
+    let parent = name.parent()?;
+    let tag = match parent.kind() {
+        UNION | VALUE | ALIAS | CLASS | WHERE => HlTag::Type,
+        MODULE => HlTag::Module,
+        ATTR_DEF => HlTag::Attribute,
+        VALUE_FIELD | RECORD_PAT_FIELD => HlTag::Field,
+        IDENT_PAT => classify_ident_pat(&ast::IdentPat::cast(parent)?),
+        PATH_SEGMENT => classify_path(&parent.parent()?)?,
+        _ => return None,
+    };
+    Some(tag)
+}
+
+fn classify_ident_pat(pat: &ast::IdentPat) -> HlTag {
+    // The pattern of the whole binding, as in `(x, y)` for `x`.
+    let top = pat
+        .syntax()
+        .ancestors()
+        .take_while(|it| ast::Pat::can_cast(it.kind()))
+        .last()
+        .unwrap_or_else(|| pat.syntax().clone());
+    match top.parent() {
+        Some(it) if matches!(it.kind(), PARAM | PARAM_LIST) => HlTag::Parameter,
+        Some(it) if top == *pat.syntax() => {
+            match ast::LetDecl::cast(it) {
+                Some(decl) if decl.param_list().is_some() => HlTag::Function,
+                _ => HlTag::Variable,
+            }
+        },
+        _ => HlTag::Variable,
+    }
+}
+
+fn classify_path(path: &SyntaxNode) -> Option<HlTag> {
+    let parent = path.parent()?;
+    let tag = match parent.kind() {
+        // `Std.List` in `Std.List.map`.
+        PATH => HlTag::Module,
+        UNION | TUPLE_VALUE_PAT => HlTag::UnionCase,
+        PATH_TYPE | CONSTRAINT | WHERE => HlTag::Type,
+        IMPORT | PREAMBLE => HlTag::Module,
+        PATH_EXPR => {
+            let is_case = path
+                .to_string()
+                .rsplit('.')
+                .next()
+                .is_some_and(|it| it.starts_with(char::is_uppercase));
+            let is_fun = parent
+                .parent()
+                .and_then(ast::AppExpr::cast)
+                .is_some_and(|app| app.fun().is_some_and(|fun| fun.syntax() == &parent));
+            match (is_case, is_fun) {
+                (true, _) => HlTag::UnionCase,
+                (false, true) => HlTag::Function,
+                (false, false) => HlTag::Variable,
+            }
+        },
+        _ => return None,
+    };
+    Some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceFile;
+
+    /// Checks the tags of `text`, written as `<tag>text</tag>` around each
+    /// token but punctuation.
+    #[track_caller]
+    fn check(text: &str, expected: &str) {
+        let file = SourceFile::parse(text);
+        let node = file.syntax_node();
+        let mut actual = String::new();
+        for token in tokens(&node) {
+            match classify_token(&token) {
+                Some(HlTag::Punctuation) | None => actual.push_str(token.text()),
+                Some(tag) => write!(actual, "<{tag}>{}</{tag}>", token.text()).unwrap(),
+            }
+        }
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn highlight_items() {
+        check(
+            "\
+/// Maybe.
+union Option [a] = Some[a] | None
+attribute Inline
+[<Inline>]
+let map f (x :: _) = f x
+module M =
+    let y = Std.List.length xs // none
+",
+            "\
+<doc_comment>/// Maybe.</doc_comment>
+<keyword>union</keyword> <type>Option</type> [<type_var>a</type_var>] = <union_case>Some</union_case>[<type>a</type>] | <union_case>None</union_case>
+<keyword>attribute</keyword> <attribute>Inline</attribute>
+<attribute>[<Inline>]</attribute>
+<keyword>let</keyword> <function>map</function> <parameter>f</parameter> (<parameter>x</parameter> <operator>::</operator> _) = <function>f</function> <variable>x</variable>
+<keyword>module</keyword> <module>M</module> =
+    <keyword>let</keyword> <variable>y</variable> = <module>Std</module>.<module>List</module>.<function>length</function> <variable>xs</variable> <comment>// none</comment>
+",
+        );
+    }
+
+    #[test]
+    fn highlight_exprs() {
+        check(
+            r#"let f x = if x > 0 then Some(f"{x}") else match x with | 'a' -> 1.5 | _ -> true"#,
+            r#"<keyword>let</keyword> <function>f</function> <parameter>x</parameter> = <keyword>if</keyword> <variable>x</variable> <operator>></operator> <int_number>0</int_number> <keyword>then</keyword> <union_case>Some</union_case>(<f_string>f"{x}"</f_string>) <keyword>else</keyword> <keyword>match</keyword> <variable>x</variable> <keyword>with</keyword> | <char>'a'</char> -> <float_number>1.5</float_number> | _ -> <bool>true</bool>"#,
+        );
+    }
+
+    #[test]
+    fn render() {
+        let file = SourceFile::parse("let x = \"<a>\"");
+        let node = file.syntax_node();
+        assert_eq!(
+            highlight_as_ansi(&node),
+            "\x1b[35mlet\x1b[0m x = \x1b[32m\"<a>\"\x1b[0m"
+        );
+        let html = highlight_as_html(&node);
+        assert!(html.starts_with("<style>"));
+        assert!(html.ends_with(
+            "<pre><code><span class=\"keyword\">let</span> <span class=\"variable\">x</span> \
+             <span class=\"punctuation\">=</span> <span class=\"string\">\"&lt;a&gt;\"</span></code></pre>\n"
+        ));
+    }
+}
@@ -1,13 +1,25 @@
 #![allow(unused)]
 
+pub mod algo;
 pub mod ast;
+mod ast_id_map;
+pub mod highlight;
 mod parsing;
+mod ptr;
+pub mod ssr;
+#[cfg(feature = "serde")]
+mod serde_impls;
 mod syntax_error;
 mod syntax_node;
+pub mod ted;
+#[cfg(test)]
+mod tests;
+mod text_edit;
+mod validation;
 
 use std::marker::PhantomData;
 
-pub use parser::{SyntaxKind, T};
+pub use parser::{ErrorCode, SyntaxKind, T};
 pub use rowan::{
     Direction, GreenNode, NodeOrToken, SyntaxText, TextRange, TextSize, TokenAtOffset, WalkEvent,
     api::Preorder,
@@ -16,11 +28,14 @@ use triomphe::Arc;
 
 pub use crate::{
     ast::{AstNode, AstToken},
+    ast_id_map::{AstIdMap, AstIdNode, ErasedFileAstId, FileAstId},
+    ptr::{AstPtr, SyntaxNodePtr},
     syntax_error::SyntaxError,
     syntax_node::{
         FsicLanguage, PreorderWithTokens, SyntaxElement, SyntaxElementChildren, SyntaxNode,
         SyntaxNodeChildren, SyntaxToken, SyntaxTreeBuilder,
     },
+    text_edit::{Indel, TextEdit, TextEditBuilder},
 };
 
 /// `Parse` is the result of the parsing: a syntax tree and a collection of
@@ -68,9 +83,53 @@ impl<T> Parse<T> {
         } else {
             vec![]
         };
-        // validation::validate(&self.syntax_node(), &mut errors);
+        validation::validate(&self.syntax_node(), &mut errors);
         errors
     }
+
+    /// Renders the tree in the indented `KIND@range "text"` format, followed
+    /// by the errors with their ranges. Meant for tests and debugging.
+    pub fn debug_dump(&self) -> String {
+        self.dump(true)
+    }
+
+    /// Like [`Parse::debug_dump`], but without the trivia tokens.
+    pub fn debug_dump_compact(&self) -> String {
+        self.dump(false)
+    }
+
+    fn dump(&self, with_trivia: bool) -> String {
+        use std::fmt::Write;
+
+        let mut buf = String::new();
+        let mut indent = 0;
+        for event in self.syntax_node().preorder_with_tokens() {
+            match event {
+                WalkEvent::Enter(NodeOrToken::Node(node)) => {
+                    writeln!(buf, "{:indent$}{node:?}", "").unwrap();
+                    indent += 2;
+                },
+                WalkEvent::Enter(NodeOrToken::Token(token)) => {
+                    if with_trivia || !token.kind().is_trivia() {
+                        writeln!(buf, "{:indent$}{token:?}", "").unwrap();
+                    }
+                },
+                WalkEvent::Leave(NodeOrToken::Node(_)) => indent -= 2,
+                WalkEvent::Leave(NodeOrToken::Token(_)) => (),
+            }
+        }
+        for err in self.errors() {
+            let code = err.code().as_str();
+            writeln!(buf, "error[{code}] {:?}: {err}", err.range()).unwrap();
+            for (range, message) in err.labels() {
+                writeln!(buf, "  label {range:?}: {message}").unwrap();
+            }
+            for note in err.notes() {
+                writeln!(buf, "  note: {note}").unwrap();
+            }
+        }
+        buf
+    }
 }
 
 impl<T: AstNode> Parse<T> {
@@ -117,106 +176,112 @@ impl Parse<SyntaxNode> {
     }
 }
 
-pub use crate::ast::Module;
+pub use crate::ast::SourceFile;
 
-impl Module {
-    pub fn parse(text: &str) -> Parse<Module> {
+impl SourceFile {
+    pub fn parse(text: &str) -> Parse<SourceFile> {
         let (green, errors) = parsing::parse_text(text);
 
         let root = SyntaxNode::new_root(green.clone());
-        assert_eq!(root.kind(), SyntaxKind::MODULE);
+        assert_eq!(root.kind(), SyntaxKind::SOURCE_FILE);
 
         Parse::new(green, errors)
     }
 }
 
+impl Parse<SourceFile> {
+    /// Applies `edit` to the text of the file and parses the result, reusing
+    /// the old tree outside of the edited item when possible.
+    pub fn reparse(&self, edit: &Indel) -> Parse<SourceFile> {
+        self.incremental_reparse(edit)
+            .unwrap_or_else(|| self.full_reparse(edit))
+    }
+
+    fn incremental_reparse(&self, edit: &Indel) -> Option<Parse<SourceFile>> {
+        let errors = self.errors.as_deref().unwrap_or_default();
+        let (green, errors, _reparsed) =
+            parsing::incremental_reparse(&self.syntax_node(), edit, errors)?;
+        Some(Parse::new(green, errors))
+    }
+
+    fn full_reparse(&self, edit: &Indel) -> Parse<SourceFile> {
+        let mut text = self.syntax_node().to_string();
+        edit.apply(&mut text);
+        SourceFile::parse(&text)
+    }
+}
+
+/// Parses `text` as a single `N`. The root is an `ERROR` node if `text` is
+/// not exactly one `N`, so [`Parse::tree`] should only be called when there
+/// are no errors.
+fn parse_single<N: AstNode>(text: &str, entry: parser::TopEntryPoint) -> Parse<N> {
+    let (green, errors) = parsing::parse_text_at(text, entry);
+
+    let root = SyntaxNode::new_root(green.clone());
+    assert!(N::can_cast(root.kind()) || root.kind() == SyntaxKind::ERROR);
+
+    Parse::new(green, errors)
+}
+
+impl ast::Expr {
+    pub fn parse(text: &str) -> Parse<ast::Expr> {
+        parse_single(text, parser::TopEntryPoint::Expr)
+    }
+}
+
+impl ast::Type {
+    pub fn parse(text: &str) -> Parse<ast::Type> {
+        parse_single(text, parser::TopEntryPoint::Type)
+    }
+}
+
+impl ast::Pat {
+    pub fn parse(text: &str) -> Parse<ast::Pat> {
+        parse_single(text, parser::TopEntryPoint::Pattern)
+    }
+}
+
+impl ast::Item {
+    pub fn parse(text: &str) -> Parse<ast::Item> {
+        parse_single(text, parser::TopEntryPoint::Item)
+    }
+}
+
 #[test]
 fn api_walkthrough() {
     let source_code = "let main\nmodule It =\n  let main\n  let main\nlet main";
 
-    let parse = Module::parse(source_code);
+    let parse = SourceFile::parse(source_code);
     assert!(parse.errors().is_empty());
 
-    let module: Module = parse.tree();
-
-    let mut buf = String::new();
-    let mut indent = 0;
-    for event in module.syntax().preorder_with_tokens() {
-        match event {
-            WalkEvent::Enter(node) => {
-                let text = match &node {
-                    NodeOrToken::Node(it) => "".to_string(),
-                    NodeOrToken::Token(it) => format!(" {:?}", it.text()),
-                };
-                use std::fmt::Write;
-                writeln!(
-                    buf,
-                    "{:indent$}{:?}{}",
-                    " ",
-                    node.kind(),
-                    text,
-                    indent = indent
-                )
-                .unwrap();
-                indent += 2;
-            },
-            WalkEvent::Leave(_) => indent -= 2,
-        }
-    }
-    //     assert_eq!(indent, 0);
-    //     assert_eq!(
-    //         buf.trim(),
-    //         r#"
-    // "let main" MODULE
-    //   "let main" LET_DECL
-    //     "let" LET_KW
-    //     " " WHITESPACE
-    //     "main" NAME
-    //       "main" IDENT
-    // "#
-    //         .trim()
-    //     );
-    assert_eq!(indent, 0);
     assert_eq!(
-        buf.trim(),
-        r#"
-MODULE
-  LET_DECL
-    LET_KW "let"
-    WHITESPACE " "
-    NAME
-      IDENT "main"
-  NEWLINE "\n"
-  NESTED_MODULE
-    MODULE_KW "module"
-    WHITESPACE " "
-    NAME
-      IDENT "It"
-    WHITESPACE " "
-    EQ "="
-    NEWLINE "\n"
-    WHITESPACE " "
-    WHITESPACE " "
-    LET_DECL
-      LET_KW "let"
-      WHITESPACE " "
-      NAME
-        IDENT "main"
-    NEWLINE "\n"
-    WHITESPACE " "
-    WHITESPACE " "
-    LET_DECL
-      LET_KW "let"
-      WHITESPACE " "
-      NAME
-        IDENT "main"
-  NEWLINE "\n"
-  LET_DECL
-    LET_KW "let"
-    WHITESPACE " "
-    NAME
-      IDENT "main"
+        parse.debug_dump_compact(),
+        r#"SOURCE_FILE@0..51
+  LET_DECL@0..8
+    LET_KW@0..3 "let"
+    IDENT_PAT@4..8
+      NAME@4..8
+        IDENT@4..8 "main"
+  MODULE@9..42
+    MODULE_KW@9..15 "module"
+    NAME@16..18
+      IDENT@16..18 "It"
+    EQ@19..20 "="
+    LET_DECL@23..31
+      LET_KW@23..26 "let"
+      IDENT_PAT@27..31
+        NAME@27..31
+          IDENT@27..31 "main"
+    LET_DECL@34..42
+      LET_KW@34..37 "let"
+      IDENT_PAT@38..42
+        NAME@38..42
+          IDENT@38..42 "main"
+  LET_DECL@43..51
+    LET_KW@43..46 "let"
+    IDENT_PAT@47..51
+      NAME@47..51
+        IDENT@47..51 "main"
 "#
-        .trim()
     );
 }
@@ -1,17 +1,26 @@
-use crate::{SyntaxError, SyntaxTreeBuilder, TextRange, syntax_node::GreenNode};
+mod reparsing;
+
+pub(crate) use self::reparsing::incremental_reparse;
+use crate::{ErrorCode, SyntaxError, SyntaxTreeBuilder, TextRange, syntax_node::GreenNode};
 
 pub(crate) fn parse_text(text: &str) -> (GreenNode, Vec<SyntaxError>) {
+    parse_text_at(text, parser::TopEntryPoint::SourceFile)
+}
+
+pub(crate) fn parse_text_at(
+    text: &str,
+    entry: parser::TopEntryPoint,
+) -> (GreenNode, Vec<SyntaxError>) {
     let lexed = parser::LexedStr::new(text);
     let parser_input = lexed.to_input();
-    let parser_output = parser::TopEntryPoint::SourceFile.parse(&parser_input);
-    let (node, errors, _eof) = build_tree(lexed, parser_output);
-    (node, errors)
+    let parser_output = entry.parse(&parser_input);
+    build_tree(lexed, parser_output)
 }
 
 pub(crate) fn build_tree(
     lexed: parser::LexedStr<'_>,
     parser_output: parser::Output,
-) -> (GreenNode, Vec<SyntaxError>, bool) {
+) -> (GreenNode, Vec<SyntaxError>) {
     let mut builder = SyntaxTreeBuilder::default();
 
     let is_eof = lexed.intersperse_trivia(&parser_output, &mut |step| {
@@ -19,9 +28,7 @@ pub(crate) fn build_tree(
             parser::StrStep::Token { kind, text } => builder.token(kind, text),
             parser::StrStep::Enter { kind } => builder.start_node(kind),
             parser::StrStep::Exit => builder.finish_node(),
-            parser::StrStep::Error { msg, pos } => {
-                builder.error(msg.to_owned(), pos.try_into().unwrap())
-            },
+            parser::StrStep::Error { err } => builder.error(syntax_error(&lexed, err)),
         }
     });
 
@@ -32,8 +39,31 @@ pub(crate) fn build_tree(
             text_range.start.try_into().unwrap(),
             text_range.end.try_into().unwrap(),
         );
-        errors.push(SyntaxError::new(err, text_range))
+        errors.push(SyntaxError::new(ErrorCode::InvalidToken, err, text_range))
     }
 
-    (node, errors, is_eof)
+    // The parser consumes all of its input, so the tree covers the text.
+    debug_assert!(is_eof, "the parser left input behind");
+    debug_assert_eq!(node.to_string(), lexed.as_str());
+
+    (node, errors)
+}
+
+fn syntax_error(lexed: &parser::LexedStr<'_>, err: &parser::ParseError) -> SyntaxError {
+    let text_range = |range| {
+        let range = lexed.input_text_range(range);
+        TextRange::new(
+            range.start.try_into().unwrap(),
+            range.end.try_into().unwrap(),
+        )
+    };
+
+    let mut res = SyntaxError::new(err.code, err.message.clone(), text_range(err.range));
+    for label in &err.labels {
+        res = res.with_label(text_range(label.range), label.message.clone());
+    }
+    for note in &err.notes {
+        res = res.with_note(note.clone());
+    }
+    res
 }
@@ -0,0 +1,332 @@
+//! Reparsing of a single item after an edit, so that editors don't have to
+//! reparse the whole file on every keystroke.
+//!
+//! The parser is sensitive to layout, so an item is reparsed as if it were at
+//! its place in the file, and the new item is only accepted if the file would
+//! be parsed the same way around it. Otherwise an enclosing item is tried, and
+//! if there is none, the whole file is reparsed.
+
+use std::iter;
+
+use parser::{ErrorCode, LexedStr, TopEntryPoint};
+
+use crate::{
+    AstNode, NodeOrToken, SyntaxError,
+    SyntaxKind::{self, *},
+    SyntaxNode, T, TextRange, TextSize, ast,
+    parsing::build_tree,
+    syntax_node::GreenNode,
+    text_edit::Indel,
+};
+
+/// Reparses the smallest item around `edit` that can be reparsed on its own,
+/// and splices it into the tree of `root`. Returns the new tree and errors,
+/// along with the range of the reparsed item in the new text.
+pub(crate) fn incremental_reparse(
+    root: &SyntaxNode,
+    edit: &Indel,
+    errors: &[SyntaxError],
+) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    let text = root.to_string();
+    let covering = match root.covering_element(edit.delete) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent()?,
+    };
+    covering
+        .ancestors()
+        .filter(is_reparsable)
+        .find_map(|item| reparse_item(&text, &item, edit, errors))
+}
+
+/// Items of a file, a module or a class. A `let` of a `let ... in` is not,
+/// as the expression around it depends on where it ends, and neither is a
+/// `module` at the start of a file, which may turn into the preamble.
+fn is_reparsable(node: &SyntaxNode) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind() {
+        SOURCE_FILE if node.kind() == MODULE => node.prev_sibling().is_some(),
+        SOURCE_FILE | MODULE | CLASS | WHERE => ast::Item::can_cast(node.kind()),
+        _ => false,
+    }
+}
+
+fn is_item_start(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        ATTRIBUTE
+            | T![let]
+            | T![module]
+            | T![import]
+            | T![attribute]
+            | T![class]
+            | T![where]
+            | T![union]
+            | T![value]
+            | T![alias]
+    )
+}
+
+fn reparse_item(
+    text: &str,
+    item: &SyntaxNode,
+    edit: &Indel,
+    errors: &[SyntaxError],
+) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    let range = item.text_range();
+    // The first token decides what kind of item it is, and may be mentioned in
+    // errors of the construct before it.
+    let first_token = item.first_token()?.text_range();
+    if !(first_token.end() < edit.delete.start() && edit.delete.end() <= range.end()) {
+        return None;
+    }
+    let start = usize::from(range.start());
+    let end = usize::from(range.end());
+
+    // The item starts a line, as it would have to be recognized in the
+    // middle of one by the construct before it.
+    let (line, col) = line_col(text, start);
+    if !text[start - col..start].chars().all(|c| c == ' ') {
+        return None;
+    }
+    // The last token can't grow into the text after the item, nor a block
+    // comment cross its boundary.
+    if !text[end..].chars().next().is_none_or(char::is_whitespace) {
+        return None;
+    }
+
+    // The next item must start a line at or to the left of this one: the
+    // item would end there whatever it contains.
+    let next = iter::successors(item.last_token()?.next_token(), |it| it.next_token())
+        .find(|it| !it.kind().is_trivia());
+    let next_start = match next {
+        Some(next) => {
+            let next_start = next.text_range().start();
+            let (_, next_col) = line_col(text, next_start.into());
+            let on_new_line = text[end..next_start.into()].contains('\n');
+            if !(on_new_line && next_col <= col && is_item_start(next.kind())) {
+                return None;
+            }
+            next_start
+        },
+        None => TextSize::of(text),
+    };
+
+    let mut new_text = item.text().to_string();
+    let delete = edit.delete.checked_sub(range.start())?;
+    Indel::replace(delete, edit.insert.clone()).apply(&mut new_text);
+    if [new_text.as_str(), &text[start..end]]
+        .iter()
+        .any(|it| it.contains("/*") || it.contains("*/"))
+    {
+        return None;
+    }
+
+    let lexed = LexedStr::new(&new_text);
+    if lexed.is_empty() || lexed.kind(lexed.len() - 1).is_trivia() {
+        return None;
+    }
+    let input = lexed.to_input_at(line as u32, col as u32);
+    // A line at or to the left of the first one would end the item.
+    let mut prev = input.token(0);
+    for i in 1.. {
+        let token = input.token(i);
+        if token.kind == EOF {
+            break;
+        }
+        if token.line != prev.line && token.col as usize <= col {
+            return None;
+        }
+        prev = token;
+    }
+
+    let output = TopEntryPoint::Item.parse(&input);
+    let (green, new_errors) = build_tree(lexed, output);
+    if SyntaxNode::new_root(green.clone()).kind() != item.kind() {
+        return None;
+    }
+    // An error at the end of the item is about the token after it, which
+    // the reparse didn't see.
+    let new_end = TextSize::of(&new_text);
+    if new_errors
+        .iter()
+        .flat_map(|err| err.ranges())
+        .any(|it| it.is_empty() && it.start() == new_end)
+    {
+        return None;
+    }
+
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    for err in errors {
+        if err
+            .ranges()
+            .all(|it| it.end() <= range.start() || first_token.contains_range(it))
+        {
+            before.push(err.clone());
+        } else if err.ranges().all(|it| it.start() > next_start) {
+            let shift = |it: TextRange| it + TextSize::of(&edit.insert) - edit.delete.len();
+            after.push(err.clone().map_ranges(shift));
+        } else if !err.ranges().all(|it| range.contains_range(it))
+            || err.range().start() == range.end()
+        {
+            // Errors of the item are replaced with the new ones, anything
+            // else around its boundary may change with it.
+            return None;
+        }
+    }
+    let new_errors = new_errors
+        .into_iter()
+        .map(|err| err.map_ranges(|it| it + range.start()));
+
+    // Parse errors come before the errors of the lexer, as in a fresh parse.
+    let mut errors: Vec<_> = before.into_iter().chain(new_errors).chain(after).collect();
+    errors.sort_by_key(|err| err.code() == ErrorCode::InvalidToken);
+
+    let new_range = TextRange::at(range.start(), new_end);
+    Some((item.replace_with(green), errors, new_range))
+}
+
+/// The zero-based line and column of `offset`, in bytes.
+fn line_col(text: &str, offset: usize) -> (usize, usize) {
+    let line_start = text[..offset].rfind('\n').map_or(0, |it| it + 1);
+    let line = text[..line_start].matches('\n').count();
+    (line, offset - line_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+    use crate::SourceFile;
+
+    /// Applies an edit to `before`, where the deleted range is between two
+    /// `$0` markers, or empty at a single one. Checks that the reparse is the
+    /// same as a fresh parse, and that `reparsed` is the text of the item that
+    /// was reparsed, `None` if the whole file was.
+    #[track_caller]
+    fn check(before: &str, insert: &str, reparsed: Option<&str>) {
+        let (start, end) = match before.match_indices("$0").collect::<Vec<_>>().as_slice() {
+            [(start, _)] => (*start, *start),
+            [(start, _), (end, _)] => (*start, *end - 2),
+            _ => panic!("expected one or two `$0` markers"),
+        };
+        let before = before.replace("$0", "");
+        let delete = TextRange::new((start as u32).into(), (end as u32).into());
+        let edit = Indel::replace(delete, insert.to_owned());
+
+        let old = SourceFile::parse(&before);
+        let errors = old.errors.as_deref().unwrap_or_default();
+        let incremental = incremental_reparse(&old.syntax_node(), &edit, errors);
+
+        let mut after = before.clone();
+        edit.apply(&mut after);
+        let actual = incremental
+            .as_ref()
+            .map(|(_, _, range)| &after[usize::from(range.start())..usize::from(range.end())]);
+        assert_eq!(actual, reparsed);
+
+        let fresh = SourceFile::parse(&after);
+        let reparse = old.reparse(&edit);
+        assert_eq!(reparse.syntax_node().to_string(), after);
+        assert_eq!(reparse.green, fresh.green);
+        assert_eq!(reparse.errors(), fresh.errors());
+    }
+
+    #[test]
+    fn reparse_item() {
+        check(
+            "let x = 1\nmodule M =\n    let y = $01$0\n    let z\nlet w",
+            "92",
+            Some("let y = 92"),
+        );
+        check(
+            "let f x =\n    x$0\nlet g",
+            "\n    x + 1",
+            Some("let f x =\n    x\n    x + 1"),
+        );
+        check(
+            "class Eq a =\n    let eq : a -> a -> bool$0\n    let ne",
+            " = f",
+            Some("let eq : a -> a -> bool = f"),
+        );
+    }
+
+    #[test]
+    fn reparse_enclosing_item() {
+        // The new line would end the inner `let`, but not the module.
+        check(
+            "let x\nmodule M =\n    let y = 1$0\nlet w",
+            "\n  z",
+            Some("module M =\n    let y = 1\n  z"),
+        );
+        // The edit touches the first token of the inner `let`.
+        check(
+            "let x\nmodule M =\n    $0let y = 1\nlet w",
+            "[<A>] ",
+            Some("module M =\n    [<A>] let y = 1"),
+        );
+        // A `module` at the start of the file may become the preamble.
+        check("module M =\n    let y = 1$0\nlet w", "\n  z", None);
+    }
+
+    #[test]
+    fn reparse_keeps_errors() {
+        check("let = 1\nlet x = 1$0\nlet = 2\n@", "0", Some("let x = 10"));
+        check("let x = (1 + $0) y\nlet y", "2", Some("let x = (1 + 2) y"));
+        check(
+            "let x = (1 + 2$0) y\nlet y",
+            " +",
+            Some("let x = (1 + 2 +) y"),
+        );
+    }
+
+    #[test]
+    fn full_reparse() {
+        // No item around the edit.
+        check("let x = 1\n$0\nlet y", "let z", None);
+        // The new text continues on the next line, or mentions it in an
+        // error.
+        check("let x = 1$0\nlet y", " +", None);
+        check("module M =\n    let x = 1$0\nlet y", "\nlet z", None);
+        check("let x = 1$0\n: int", "0", None);
+        // Block comments may span items.
+        check("let x = 1$0\nlet y = 2 */", " /*", None);
+    }
+
+    /// Every small edit of the parser's test corpus reparses to the same
+    /// tree as a fresh parse.
+    #[test]
+    fn reparse_corpus() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../parser/test_data/parser/ok");
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().unwrap_or_default() != "fsic" {
+                continue;
+            }
+            let text = fs::read_to_string(path).unwrap();
+            let old = SourceFile::parse(&text);
+            // The errors of the parser, without the validation.
+            let errors = old.errors.as_deref().unwrap_or_default();
+            for offset in 0..text.len() as u32 {
+                for (len, insert) in [(0, "x"), (0, "\n"), (1, "")] {
+                    let delete = TextRange::at(offset.into(), len.into());
+                    let edit = Indel::replace(delete, insert.to_owned());
+                    let Some((green, errors, _)) =
+                        incremental_reparse(&old.syntax_node(), &edit, errors)
+                    else {
+                        continue;
+                    };
+                    let mut after = text.clone();
+                    edit.apply(&mut after);
+                    let fresh = SourceFile::parse(&after);
+                    assert_eq!(green, fresh.green, "{after}");
+                    let fresh_errors = fresh.errors.as_deref().unwrap_or_default();
+                    assert_eq!(errors, fresh_errors, "{after}");
+                }
+            }
+        }
+    }
+}
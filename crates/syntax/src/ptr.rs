@@ -0,0 +1,140 @@
+//! Pointers to syntax nodes that don't keep the tree alive.
+//!
+//! A `SyntaxNode` holds on to its whole tree, while the pointers here are
+//! just a kind and a range, resolved against the root when the node is needed
+//! again.
+//!
+//! A pointer is only valid for the exact tree it was made from, see
+//! [`crate::AstIdMap`] for ids that survive edits.
+
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use crate::{AstNode, FsicLanguage, SyntaxNode, TextRange};
+
+/// A "pointer" to a [`SyntaxNode`], via location in the source code.
+pub type SyntaxNodePtr = rowan::ast::SyntaxNodePtr<FsicLanguage>;
+
+/// Like `SyntaxNodePtr`, but remembers the type of node.
+pub struct AstPtr<N: AstNode> {
+    raw: SyntaxNodePtr,
+    _ty: PhantomData<fn() -> N>,
+}
+
+impl<N: AstNode> fmt::Debug for AstPtr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AstPtr").field(&self.raw).finish()
+    }
+}
+
+impl<N: AstNode> Copy for AstPtr<N> {}
+impl<N: AstNode> Clone for AstPtr<N> {
+    fn clone(&self) -> AstPtr<N> {
+        *self
+    }
+}
+
+impl<N: AstNode> Eq for AstPtr<N> {}
+
+impl<N: AstNode> PartialEq for AstPtr<N> {
+    fn eq(&self, other: &AstPtr<N>) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<N: AstNode> Hash for AstPtr<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
+}
+
+impl<N: AstNode> AstPtr<N> {
+    pub fn new(node: &N) -> AstPtr<N> {
+        AstPtr {
+            raw: SyntaxNodePtr::new(node.syntax()),
+            _ty: PhantomData,
+        }
+    }
+
+    /// Finds the node in `root`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `root` is not the tree that the pointer was made from.
+    pub fn to_node(&self, root: &SyntaxNode) -> N {
+        let syntax_node = self.raw.to_node(root);
+        N::cast(syntax_node).unwrap()
+    }
+
+    pub fn try_to_node(&self, root: &SyntaxNode) -> Option<N> {
+        N::cast(self.raw.try_to_node(root)?)
+    }
+
+    pub fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.raw
+    }
+
+    pub fn text_range(&self) -> TextRange {
+        self.raw.text_range()
+    }
+
+    pub fn cast<U: AstNode>(self) -> Option<AstPtr<U>> {
+        if !U::can_cast(self.raw.kind()) {
+            return None;
+        }
+        Some(AstPtr {
+            raw: self.raw,
+            _ty: PhantomData,
+        })
+    }
+
+    pub fn upcast<M: AstNode>(self) -> AstPtr<M>
+    where
+        N: Into<M>,
+    {
+        AstPtr {
+            raw: self.raw,
+            _ty: PhantomData,
+        }
+    }
+
+    /// Like `SyntaxNodePtr::cast` but the trait bounds work out.
+    pub fn try_from_raw(raw: SyntaxNodePtr) -> Option<AstPtr<N>> {
+        N::can_cast(raw.kind()).then_some(AstPtr {
+            raw,
+            _ty: PhantomData,
+        })
+    }
+}
+
+impl<N: AstNode> From<AstPtr<N>> for SyntaxNodePtr {
+    fn from(ptr: AstPtr<N>) -> SyntaxNodePtr {
+        ptr.raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SourceFile, ast};
+
+    #[test]
+    fn test_local_syntax_ptr() {
+        let file = SourceFile::parse("module M =\n    let x = 1\nlet y").tree();
+        let decl = file
+            .syntax()
+            .descendants()
+            .find_map(ast::LetDecl::cast)
+            .unwrap();
+        let ptr = AstPtr::new(&decl);
+        assert_eq!(ptr.to_node(file.syntax()).syntax(), decl.syntax());
+        assert_eq!(ptr.cast::<ast::Module>(), None);
+
+        let item = ptr.upcast::<ast::Item>();
+        assert_eq!(item.syntax_node_ptr(), ptr.syntax_node_ptr());
+        assert!(matches!(item.to_node(file.syntax()), ast::Item::LetDecl(_)));
+    }
+}
@@ -0,0 +1,316 @@
+//! Serialization of parse results, for tools outside of Rust.
+//!
+//! A [`Parse`] is serialized as its tree and its errors:
+//!
+//! ```json
+//! {
+//!   "root": {
+//!     "kind": "SOURCE_FILE",
+//!     "range": [0, 5],
+//!     "text": "let x",
+//!     "children": [{
+//!       "kind": "LET_DECL",
+//!       "range": [0, 5],
+//!       "text": "let x",
+//!       "children": [
+//!         { "kind": "LET_KW", "range": [0, 3], "text": "let" },
+//!         ...
+//!       ]
+//!     }]
+//!   },
+//!   "errors": [
+//!     {
+//!       "code": "E0001",
+//!       "message": "expected a pattern, found end of input",
+//!       "range": [5, 5],
+//!       "labels": [{ "range": [0, 3], "message": "..." }],
+//!       "notes": ["..."]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Kinds are the names of [`SyntaxKind`]s, and ranges are `[start, end]`
+//! offsets in bytes. A node is told apart from a token by its `children`.
+//!
+//! Deserialization rebuilds the tree from the kinds and the texts of tokens
+//! alone, ignoring the texts of nodes, so that tools can send back an edited
+//! tree without fixing up the ranges. The errors are dropped, as they describe
+//! the text before the edit: [`Parse::errors`] of the result only has the
+//! errors of validation.
+
+use std::{any::type_name, sync::OnceLock};
+
+use rustc_hash::FxHashMap;
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::Error as _,
+    ser::{SerializeSeq, SerializeStruct},
+};
+
+use crate::{
+    AstNode, NodeOrToken, Parse, SyntaxError, SyntaxKind, SyntaxNode, SyntaxToken,
+    SyntaxTreeBuilder, TextRange,
+};
+
+impl<T> Serialize for Parse<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Parse", 2)?;
+        state.serialize_field("root", &NodeRef(&self.syntax_node()))?;
+        state.serialize_field("errors", &self.errors())?;
+        state.end()
+    }
+}
+
+impl<'de, T: AstNode> Deserialize<'de> for Parse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Repr {
+            root: NodeRepr,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let mut builder = SyntaxTreeBuilder::default();
+        repr.root.build(&mut builder);
+        let parse = builder.finish();
+        let kind = parse.syntax_node().kind();
+        parse.cast().ok_or_else(|| {
+            D::Error::custom(format!(
+                "`{kind:?}` is not the root of a `{}`",
+                type_name::<T>()
+            ))
+        })
+    }
+}
+
+impl Serialize for SyntaxError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SyntaxError", 5)?;
+        state.serialize_field("code", self.code().as_str())?;
+        state.serialize_field("message", self.message())?;
+        state.serialize_field("range", &Range(self.range()))?;
+        state.serialize_field("labels", &Labels(self.labels()))?;
+        state.serialize_field("notes", self.notes())?;
+        state.end()
+    }
+}
+
+struct NodeRef<'a>(&'a SyntaxNode);
+
+impl Serialize for NodeRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let node = self.0;
+        let mut state = serializer.serialize_struct("Node", 4)?;
+        state.serialize_field("kind", &Kind(node.kind()))?;
+        state.serialize_field("range", &Range(node.text_range()))?;
+        state.serialize_field("text", &Text(node))?;
+        state.serialize_field("children", &Children(node))?;
+        state.end()
+    }
+}
+
+struct TokenRef<'a>(&'a SyntaxToken);
+
+impl Serialize for TokenRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let token = self.0;
+        let mut state = serializer.serialize_struct("Token", 3)?;
+        state.serialize_field("kind", &Kind(token.kind()))?;
+        state.serialize_field("range", &Range(token.text_range()))?;
+        state.serialize_field("text", token.text())?;
+        state.end()
+    }
+}
+
+struct Text<'a>(&'a SyntaxNode);
+
+impl Serialize for Text<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0.text())
+    }
+}
+
+struct Children<'a>(&'a SyntaxNode);
+
+impl Serialize for Children<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        for child in self.0.children_with_tokens() {
+            match &child {
+                NodeOrToken::Node(it) => seq.serialize_element(&NodeRef(it))?,
+                NodeOrToken::Token(it) => seq.serialize_element(&TokenRef(it))?,
+            }
+        }
+        seq.end()
+    }
+}
+
+struct Labels<'a>(&'a [(TextRange, String)]);
+
+impl Serialize for Labels<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Label<'a> {
+            range: Range,
+            message: &'a str,
+        }
+
+        serializer.collect_seq(self.0.iter().map(|(range, message)| {
+            Label {
+                range: Range(*range),
+                message,
+            }
+        }))
+    }
+}
+
+struct Range(TextRange);
+
+impl Serialize for Range {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let range = self.0;
+        [u32::from(range.start()), u32::from(range.end())].serialize(serializer)
+    }
+}
+
+struct Kind(SyntaxKind);
+
+impl Serialize for Kind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:?}", self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Kind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        static KINDS: OnceLock<FxHashMap<String, SyntaxKind>> = OnceLock::new();
+
+        let kinds = KINDS.get_or_init(|| {
+            (0..SyntaxKind::__LAST as u16)
+                .map(SyntaxKind::from)
+                .map(|kind| (format!("{kind:?}"), kind))
+                .collect()
+        });
+        let name = String::deserialize(deserializer)?;
+        match kinds.get(&name) {
+            Some(&kind) => Ok(Kind(kind)),
+            None => Err(D::Error::custom(format!("unknown syntax kind `{name}`"))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct NodeRepr {
+    kind: Kind,
+    children: Vec<ElementRepr>,
+}
+
+#[derive(Deserialize)]
+struct TokenRepr {
+    kind: Kind,
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ElementRepr {
+    Node(NodeRepr),
+    Token(TokenRepr),
+}
+
+impl NodeRepr {
+    fn build(&self, builder: &mut SyntaxTreeBuilder) {
+        builder.start_node(self.kind.0);
+        for child in &self.children {
+            match child {
+                ElementRepr::Node(it) => it.build(builder),
+                ElementRepr::Token(it) => builder.token(it.kind.0, &it.text),
+            }
+        }
+        builder.finish_node();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{SourceFile, ast};
+
+    #[test]
+    fn schema() {
+        let parse = SourceFile::parse("let rec x");
+        let actual = serde_json::to_value(&parse).unwrap();
+        let expected = json!({
+            "root": {
+                "kind": "SOURCE_FILE",
+                "range": [0, 9],
+                "text": "let rec x",
+                "children": [{
+                    "kind": "LET_DECL",
+                    "range": [0, 9],
+                    "text": "let rec x",
+                    "children": [
+                        { "kind": "LET_KW", "range": [0, 3], "text": "let" },
+                        { "kind": "WHITESPACE", "range": [3, 4], "text": " " },
+                        { "kind": "REC_KW", "range": [4, 7], "text": "rec" },
+                        { "kind": "WHITESPACE", "range": [7, 8], "text": " " },
+                        {
+                            "kind": "IDENT_PAT",
+                            "range": [8, 9],
+                            "text": "x",
+                            "children": [{
+                                "kind": "NAME",
+                                "range": [8, 9],
+                                "text": "x",
+                                "children": [
+                                    { "kind": "IDENT", "range": [8, 9], "text": "x" },
+                                ],
+                            }],
+                        },
+                    ],
+                }],
+            },
+            "errors": [{
+                "code": "E0008",
+                "message": "only functions can be recursive",
+                "range": [8, 9],
+                "labels": [{ "range": [4, 7], "message": "declared recursive here" }],
+                "notes": [
+                    "a recursive function needs a name and parameters, as in `let rec f x = ...`",
+                ],
+            }],
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn round_trip_edited_tree() {
+        let parse = SourceFile::parse("module M =\n    let x = 1\n");
+        let json = serde_json::to_string(&parse).unwrap();
+        let back: Parse<SourceFile> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.syntax_node().green(), parse.syntax_node().green());
+
+        // The ranges and the texts of nodes are stale after the edit, and
+        // don't matter.
+        let json = json.replace(r#""text":"x"}"#, r#""text":"answer"}"#);
+        let edited: Parse<SourceFile> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            edited.syntax_node().to_string(),
+            "module M =\n    let answer = 1\n"
+        );
+        assert_eq!(
+            edited.debug_dump(),
+            SourceFile::parse("module M =\n    let answer = 1\n").debug_dump()
+        );
+
+        let err = serde_json::from_str::<Parse<SourceFile>>(
+            r#"{ "root": { "kind": "NOPE", "children": [] } }"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown syntax kind `NOPE`"));
+        let err = serde_json::from_str::<Parse<ast::Expr>>(&json).unwrap_err();
+        assert!(err.to_string().contains("`SOURCE_FILE` is not the root"));
+    }
+}
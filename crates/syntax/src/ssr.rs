@@ -0,0 +1,553 @@
+//! Structural search and replace.
+//!
+//! A rule is written as `search ==>> replacement`, where both sides are Fsic
+//! code with `$placeholders` in it, as in:
+//!
+//! ```text
+//! match $x with | Some($v) -> $v | None -> $d ==>> Option.default $d $x
+//! ```
+//!
+//! The search side is parsed with the entry points of the parser: as an
+//! expression, a type, a pattern and an item, with every one that works. It
+//! then matches the nodes of the same shape in a tree, no matter the trivia
+//! in them, and a placeholder matches any node that can stand in its place.
+//! A placeholder used several times must match the same code every time.
+//!
+//! The replacement is the text of the replacement side, with the code that
+//! the placeholders matched put in place of them and re-indented to fit. The
+//! rule is applied to that code as well, and it gets parentheses where the
+//! replacement would take it apart, as does the replacement in the code
+//! around it.
+
+use std::{fmt, str::FromStr};
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+    AstNode, Indel, NodeOrToken, Parse, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken,
+    TextRange, WalkEvent, ast,
+};
+
+/// The prefix of the identifiers that placeholders are replaced with for
+/// parsing.
+const PLACEHOLDER_PREFIX: &str = "__ssr_";
+
+/// A rule that is wrong: it doesn't parse, or it uses placeholders that
+/// aren't in the search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsrError(String);
+
+impl fmt::Display for SsrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SsrError {}
+
+#[derive(Debug, Clone)]
+pub struct SsrRule {
+    /// The search and the replacement, as parsed by each of the entry points
+    /// that can parse both.
+    patterns: Vec<(SyntaxNode, SyntaxNode)>,
+    template: String,
+}
+
+impl FromStr for SsrRule {
+    type Err = SsrError;
+
+    /// Parses a rule of the form `search ==>> replacement`.
+    fn from_str(rule: &str) -> Result<SsrRule, SsrError> {
+        let Some((search, replacement)) = rule.split_once("==>>") else {
+            return Err(SsrError(format!("expected `==>>` in `{rule}`")));
+        };
+        SsrRule::new(search, replacement)
+    }
+}
+
+impl SsrRule {
+    pub fn new(search: &str, replacement: &str) -> Result<SsrRule, SsrError> {
+        let (search, replacement) = (search.trim(), replacement.trim());
+        let search_names = placeholders(search)?;
+        if let Some(name) = placeholders(replacement)?
+            .into_iter()
+            .find(|it| !search_names.contains(it))
+        {
+            return Err(SsrError(format!("`${name}` is not in the search")));
+        }
+
+        let search_text = to_parsable(search);
+        let replacement_text = to_parsable(replacement);
+        let patterns: Vec<(SyntaxNode, SyntaxNode)> = [
+            parse_as(&search_text, &replacement_text, ast::Expr::parse),
+            parse_as(&search_text, &replacement_text, ast::Type::parse),
+            parse_as(&search_text, &replacement_text, ast::Pat::parse),
+            parse_as(&search_text, &replacement_text, ast::Item::parse),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if patterns.is_empty() {
+            return Err(SsrError(format!(
+                "`{search}` and `{replacement}` are not both expressions, types, patterns or items"
+            )));
+        }
+        Ok(SsrRule {
+            patterns,
+            template: replacement.to_owned(),
+        })
+    }
+
+    /// The matches in `root`, in order. Only the outermost of nested matches
+    /// are found; the ones in the code of their placeholders are replaced
+    /// along with them.
+    pub fn find_matches(&self, root: &SyntaxNode) -> Vec<SsrMatch> {
+        let mut res = Vec::new();
+        let mut preorder = root.preorder();
+        while let Some(event) = preorder.next() {
+            let WalkEvent::Enter(node) = event else {
+                continue;
+            };
+            let found = self.patterns.iter().find_map(|(pattern, template)| {
+                let mut placeholders = FxHashMap::default();
+                match_node(pattern, &node, &mut placeholders).then(|| {
+                    SsrMatch {
+                        node: node.clone(),
+                        placeholders,
+                        template: template.clone(),
+                    }
+                })
+            });
+            if let Some(found) = found {
+                res.push(found);
+                preorder.skip_subtree();
+            }
+        }
+        res
+    }
+
+    /// The edits that replace the matches in `root`, in order.
+    pub fn edits(&self, root: &SyntaxNode) -> Vec<Indel> {
+        self.find_matches(root)
+            .iter()
+            .map(|it| Indel::replace(it.range(), self.replacement(it)))
+            .collect()
+    }
+
+    /// The replacement of `found`, to be put at its place, in parentheses if
+    /// the code around it would take it apart.
+    pub fn replacement(&self, found: &SsrMatch) -> String {
+        let start = column(&found.node);
+        let mut placeholders = found
+            .template
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| it.text().starts_with(PLACEHOLDER_PREFIX));
+        let mut res = String::new();
+        // The column of the end of `res`, in the edited text.
+        let mut at = start;
+        let mut rest = self.template.as_str();
+        while let Some(c) = rest.chars().next() {
+            if let Some(name) = rest.strip_prefix('$').map(placeholder_name) {
+                let node = &found.placeholders[name];
+                let place = placeholders.next().and_then(|it| placeholder_expr(&it));
+                let text = self.rewrite(node, found);
+                let text = match parenthesize(&text, place.as_ref()) {
+                    true => format!("({})", reindent(&text, column(node), at + 1)),
+                    false => reindent(&text, column(node), at),
+                };
+                at = match text.rfind('\n') {
+                    Some(i) => text.len() - i - 1,
+                    None => at + text.len(),
+                };
+                res.push_str(&text);
+                rest = &rest[1 + name.len()..];
+                continue;
+            }
+            if c == '\n' {
+                // The lines of the template are relative to the match.
+                res.push('\n');
+                res.extend(std::iter::repeat_n(' ', start));
+                at = start;
+            } else {
+                res.push(c);
+                at += c.len_utf8();
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+
+        let place = ast::Expr::cast(found.node.clone());
+        match parenthesize(&reindent(&res, start, 0), place.as_ref()) {
+            true => format!("({})", reindent(&res, start, start + 1)),
+            false => res,
+        }
+    }
+
+    /// The text of `node`, the code of a placeholder of `outer`, with the
+    /// matches in it replaced. A search that is only a placeholder matches
+    /// that code again, so then only the matches inside it are.
+    fn rewrite(&self, node: &SyntaxNode, outer: &SsrMatch) -> String {
+        let mut text = node.to_string();
+        let offset = node.text_range().start();
+        let found: Vec<_> = match *node == outer.node {
+            true => {
+                node.children()
+                    .flat_map(|it| self.find_matches(&it))
+                    .collect()
+            },
+            false => self.find_matches(node),
+        };
+        for found in found.iter().rev() {
+            let range = found.range() - offset;
+            text.replace_range(
+                std::ops::Range::<usize>::from(range),
+                &self.replacement(found),
+            );
+        }
+        text
+    }
+}
+
+/// A node that matches the search of a rule, with the nodes that the
+/// placeholders matched in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsrMatch {
+    node: SyntaxNode,
+    placeholders: FxHashMap<String, SyntaxNode>,
+    /// The replacement, as parsed by the entry point of the search.
+    template: SyntaxNode,
+}
+
+impl SsrMatch {
+    pub fn node(&self) -> &SyntaxNode {
+        &self.node
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.node.text_range()
+    }
+
+    /// The node matched by `$name`.
+    pub fn placeholder(&self, name: &str) -> Option<&SyntaxNode> {
+        self.placeholders.get(name)
+    }
+}
+
+/// The names of the placeholders in `text`, in order.
+fn placeholders(text: &str) -> Result<Vec<&str>, SsrError> {
+    let mut res = Vec::new();
+    for (i, _) in text.match_indices('$') {
+        let name = placeholder_name(&text[i + 1..]);
+        if name.is_empty() {
+            return Err(SsrError(format!("expected a name after `$` in `{text}`")));
+        }
+        res.push(name);
+    }
+    Ok(res)
+}
+
+/// The name of the placeholder at the start of `text`, after the `$`.
+fn placeholder_name(text: &str) -> &str {
+    let len = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    &text[..len]
+}
+
+/// `text` with the placeholders turned into identifiers.
+fn to_parsable(text: &str) -> String {
+    text.replace('$', PLACEHOLDER_PREFIX)
+}
+
+fn parse_as<N: AstNode>(
+    search: &str,
+    replacement: &str,
+    parse: fn(&str) -> Parse<N>,
+) -> Option<(SyntaxNode, SyntaxNode)> {
+    let (search, replacement) = (parse(search), parse(replacement));
+    let ok = search.errors().is_empty() && replacement.errors().is_empty();
+    ok.then(|| (search.syntax_node(), replacement.syntax_node()))
+}
+
+/// The expression that the placeholder `token` of a replacement is, if it is
+/// one.
+fn placeholder_expr(token: &SyntaxToken) -> Option<ast::Expr> {
+    token
+        .parent_ancestors()
+        .take_while(|it| as_placeholder(it).is_some())
+        .last()
+        .and_then(ast::Expr::cast)
+}
+
+/// Whether the expression `text` needs parentheses to stand at `place`.
+fn parenthesize(text: &str, place: Option<&ast::Expr>) -> bool {
+    let Some(place) = place else {
+        return false;
+    };
+    let expr = ast::Expr::parse(text);
+    expr.errors().is_empty() && expr.tree().needs_parens_in_place_of(place)
+}
+
+/// The name of the placeholder that `node` is made of, if it is one.
+fn as_placeholder(node: &SyntaxNode) -> Option<String> {
+    let mut tokens = non_trivia_tokens(node);
+    let token = tokens.next()?;
+    if tokens.next().is_some() || token.kind() != SyntaxKind::IDENT {
+        return None;
+    }
+    let name = token.text().strip_prefix(PLACEHOLDER_PREFIX)?;
+    Some(name.to_owned())
+}
+
+/// Whether `code` can stand where the placeholder `pattern` is.
+fn fits_placeholder(pattern: &SyntaxNode, code: &SyntaxNode) -> bool {
+    let (pattern, code) = (pattern.kind(), code.kind());
+    if ast::Expr::can_cast(pattern) {
+        ast::Expr::can_cast(code)
+    } else if ast::Type::can_cast(pattern) {
+        ast::Type::can_cast(code)
+    } else if ast::Pat::can_cast(pattern) {
+        ast::Pat::can_cast(code)
+    } else {
+        pattern == code
+    }
+}
+
+fn match_node(
+    pattern: &SyntaxNode,
+    code: &SyntaxNode,
+    placeholders: &mut FxHashMap<String, SyntaxNode>,
+) -> bool {
+    if let Some(name) = as_placeholder(pattern) {
+        if !fits_placeholder(pattern, code) {
+            return false;
+        }
+        return match placeholders.get(&name) {
+            Some(prev) => code_text(prev) == code_text(code),
+            None => {
+                placeholders.insert(name, code.clone());
+                true
+            },
+        };
+    }
+    if pattern.kind() != code.kind() {
+        return false;
+    }
+    let mut patterns = non_trivia_children(pattern);
+    let mut codes = non_trivia_children(code);
+    loop {
+        match (patterns.next(), codes.next()) {
+            (None, None) => return true,
+            (Some(NodeOrToken::Node(pattern)), Some(NodeOrToken::Node(code))) => {
+                if !match_node(&pattern, &code, placeholders) {
+                    return false;
+                }
+            },
+            (Some(NodeOrToken::Token(pattern)), Some(NodeOrToken::Token(code))) => {
+                if pattern.kind() != code.kind() || pattern.text() != code.text() {
+                    return false;
+                }
+            },
+            _ => return false,
+        }
+    }
+}
+
+fn non_trivia_children(node: &SyntaxNode) -> impl Iterator<Item = SyntaxElement> {
+    node.children_with_tokens()
+        .filter(|it| !it.kind().is_trivia())
+}
+
+fn non_trivia_tokens(node: &SyntaxNode) -> impl Iterator<Item = SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| !it.kind().is_trivia())
+}
+
+/// The text of `node` without trivia, to compare code regardless of layout.
+fn code_text(node: &SyntaxNode) -> Vec<String> {
+    non_trivia_tokens(node)
+        .map(|it| it.text().to_owned())
+        .collect()
+}
+
+/// The column where `node` starts in its tree.
+fn column(node: &SyntaxNode) -> usize {
+    let mut res = 0;
+    let mut token = node.first_token().and_then(|it| it.prev_token());
+    while let Some(it) = token {
+        if it.kind() == SyntaxKind::NEWLINE {
+            break;
+        }
+        res += it.text().len();
+        token = it.prev_token();
+    }
+    res
+}
+
+/// `text`, which starts at column `from`, with the lines after the first one
+/// moved so that it can start at column `to`.
+fn reindent(text: &str, from: usize, to: usize) -> String {
+    let mut lines = text.split('\n');
+    let mut res = lines.next().unwrap_or_default().to_owned();
+    for line in lines {
+        res.push('\n');
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        let indent = (spaces + to).saturating_sub(from);
+        if !line.trim().is_empty() {
+            res.extend(std::iter::repeat_n(' ', indent));
+            res.push_str(&line[spaces..]);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceFile;
+
+    #[track_caller]
+    fn check(rule: &str, before: &str, after: &str) {
+        let rule: SsrRule = rule.parse().unwrap();
+        let file = SourceFile::parse(before);
+        assert!(file.errors().is_empty());
+        let mut text = before.to_owned();
+        for edit in rule.edits(&file.syntax_node()).iter().rev() {
+            edit.apply(&mut text);
+        }
+        assert_eq!(text, after);
+        assert!(
+            SourceFile::parse(&text).errors().is_empty(),
+            "invalid result:\n{text}"
+        );
+    }
+
+    #[test]
+    fn rename_method() {
+        check(
+            "Eq.eq $a $b ==>> Eq.equals $a $b",
+            "let f x = Eq.eq x 1 && Eq.eq (g x) (Eq.eq 2 3)",
+            "let f x = Eq.equals x 1 && Eq.equals (g x) (Eq.equals 2 3)",
+        );
+        check(
+            "Eq.eq $a $b ==>> Eq.equals $a $b",
+            "let f a b c = Eq.eq (Eq.eq a b) c",
+            "let f a b c = Eq.equals (Eq.equals a b) c",
+        );
+    }
+
+    #[test]
+    fn keeps_precedence() {
+        check(
+            "add $a $b ==>> $a + $b",
+            "let x = add 1 2 * 3",
+            "let x = (1 + 2) * 3",
+        );
+        check(
+            "$a + $b ==>> add $a $b",
+            "let x = f x + y * 2",
+            "let x = add (f x) (y * 2)",
+        );
+        check(
+            "$a - $b ==>> $b - $a",
+            "let x = a - b - c",
+            "let x = c - (b - a)",
+        );
+        check(
+            "id $x ==>> $x",
+            "let x = id (if c then a else b) + 1",
+            "let x = (if c then a else b) + 1",
+        );
+    }
+
+    #[test]
+    fn match_to_helper() {
+        check(
+            "match $x with | Some($v) -> $v | None -> $d ==>> Option.default $d $x",
+            "\
+let f o =
+    match o with
+    | Some(v) -> v
+    | None -> 0
+
+let g o =
+    match o with
+    | Some( w )  -> w
+    | None -> 1
+let h o = match o with | Some(v) -> v | None -> v
+",
+            "\
+let f o =
+    Option.default 0 o
+
+let g o =
+    Option.default 1 o
+let h o = Option.default v o
+",
+        );
+    }
+
+    #[test]
+    fn search_of_a_placeholder() {
+        // The code of `$x` matches `$x` too; only what is inside it is
+        // rewritten again.
+        check("$x ==>> ($x)", "let y = 1", "let (y) = (1)");
+        check("$x ==>> ($x)", "let y = f 1", "let (y) = ((f) (1))");
+    }
+
+    #[test]
+    fn placeholders_must_agree() {
+        check(
+            "$a + $a ==>> 2 * $a",
+            "let x = (f y) + (f  y) + (f z)",
+            "let x = 2 * (f y) + (f z)",
+        );
+    }
+
+    #[test]
+    fn other_entry_points() {
+        check(
+            "Option[$t] ==>> Maybe[$t]",
+            "let f (x : Option[Int]) : Option[List[a]] = x",
+            "let f (x : Maybe[Int]) : Maybe[List[a]] = x",
+        );
+        check(
+            "Pair($x, _) ==>> Fst($x)",
+            "let f p = match p with | Pair(x, _) -> x | Pair(_, y) -> y",
+            "let f p = match p with | Fst(x) -> x | Pair(_, y) -> y",
+        );
+    }
+
+    #[test]
+    fn reindents_placeholders() {
+        check(
+            "id $x ==>> let y = $x in\ny",
+            "\
+let f x =
+    id (match x with
+        | A -> 1
+        | B -> 2)
+",
+            "\
+let f x =
+    let y = (match x with
+             | A -> 1
+             | B -> 2) in
+    y
+",
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let err = |rule: &str| rule.parse::<SsrRule>().unwrap_err().to_string();
+        assert_eq!(err("f $x"), "expected `==>>` in `f $x`");
+        assert_eq!(err("f $x ==>> g $y"), "`$y` is not in the search");
+        assert_eq!(err("f $ ==>> g"), "expected a name after `$` in `f $`");
+        assert_eq!(
+            err("f ( ==>> g"),
+            "`f (` and `g` are not both expressions, types, patterns or items"
+        );
+    }
+}
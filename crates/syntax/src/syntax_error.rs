@@ -1,32 +1,89 @@
 use std::fmt;
 
+use parser::ErrorCode;
+
 use crate::{TextRange, TextSize};
 
 /// Represents the result of unsuccessful tokenization, parsing
 /// or tree validation.
+///
+/// Besides the message at the primary range, an error may have secondary
+/// labels pointing at related code, and notes with further explanations.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct SyntaxError(String, TextRange);
+pub struct SyntaxError {
+    code: ErrorCode,
+    message: String,
+    range: TextRange,
+    labels: Vec<(TextRange, String)>,
+    notes: Vec<String>,
+}
 
 impl SyntaxError {
-    pub fn new(message: impl Into<String>, range: TextRange) -> Self {
-        Self(message.into(), range)
+    pub fn new(code: ErrorCode, message: impl Into<String>, range: TextRange) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            range,
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+    pub fn new_at_offset(code: ErrorCode, message: impl Into<String>, offset: TextSize) -> Self {
+        Self::new(code, message, TextRange::empty(offset))
     }
-    pub fn new_at_offset(message: impl Into<String>, offset: TextSize) -> Self {
-        Self(message.into(), TextRange::empty(offset))
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
     }
 
     pub fn range(&self) -> TextRange {
-        self.1
+        self.range
+    }
+
+    pub fn labels(&self) -> &[(TextRange, String)] {
+        &self.labels
+    }
+
+    pub fn notes(&self) -> &[String] {
+        &self.notes
     }
 
     pub fn with_range(mut self, range: TextRange) -> Self {
-        self.1 = range;
+        self.range = range;
+        self
+    }
+
+    pub fn with_label(mut self, range: TextRange, message: impl Into<String>) -> Self {
+        self.labels.push((range, message.into()));
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// All the ranges of the error, the primary one first.
+    pub(crate) fn ranges(&self) -> impl Iterator<Item = TextRange> + '_ {
+        std::iter::once(self.range).chain(self.labels.iter().map(|(range, _)| *range))
+    }
+
+    /// Applies `f` to all the ranges of the error.
+    pub(crate) fn map_ranges(mut self, f: impl Fn(TextRange) -> TextRange) -> Self {
+        self.range = f(self.range);
+        for (range, _) in &mut self.labels {
+            *range = f(*range);
+        }
         self
     }
 }
 
 impl fmt::Display for SyntaxError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        self.message.fmt(f)
     }
 }
@@ -56,8 +56,7 @@ impl SyntaxTreeBuilder {
         self.inner.finish_node();
     }
 
-    pub fn error(&mut self, error: String, text_pos: TextSize) {
-        self.errors
-            .push(SyntaxError::new_at_offset(error, text_pos));
+    pub fn error(&mut self, error: SyntaxError) {
+        self.errors.push(error);
     }
 }
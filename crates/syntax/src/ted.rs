@@ -0,0 +1,505 @@
+//! Primitive tree editor, ed for trees.
+//!
+//! The `_raw`-suffixed functions insert elements as is, unsuffixed versions fix
+//! up the layout: items go on lines of their own, and the lines of moved nodes
+//! are re-indented to the column they end up at, so that the offside rule
+//! still holds. Removed nodes are re-indented as if they started at column
+//! 0, like the ones made by [`make`], so that they can be inserted elsewhere.
+//! All the trees must be mutable, see [`crate::AstNode::clone_for_update`].
+
+use std::ops::RangeInclusive;
+
+use crate::{
+    SyntaxElement,
+    SyntaxKind::{self, *},
+    SyntaxNode, SyntaxToken, T,
+    ast::{self, AstNode, edit::IndentLevel, make},
+};
+
+/// Utility trait to allow calling `ted` functions with references or owned
+/// nodes. Do not use outside of this module.
+pub trait Element {
+    fn syntax_element(self) -> SyntaxElement;
+}
+
+impl<E: Element + Clone> Element for &'_ E {
+    fn syntax_element(self) -> SyntaxElement {
+        self.clone().syntax_element()
+    }
+}
+impl Element for SyntaxElement {
+    fn syntax_element(self) -> SyntaxElement {
+        self
+    }
+}
+impl Element for SyntaxNode {
+    fn syntax_element(self) -> SyntaxElement {
+        self.into()
+    }
+}
+impl Element for SyntaxToken {
+    fn syntax_element(self) -> SyntaxElement {
+        self.into()
+    }
+}
+
+#[derive(Debug)]
+pub struct Position {
+    repr: PositionRepr,
+}
+
+#[derive(Debug)]
+enum PositionRepr {
+    FirstChild(SyntaxNode),
+    After(SyntaxElement),
+}
+
+impl Position {
+    pub fn after(elem: impl Element) -> Position {
+        let repr = PositionRepr::After(elem.syntax_element());
+        Position { repr }
+    }
+    pub fn before(elem: impl Element) -> Position {
+        let elem = elem.syntax_element();
+        let repr = match elem.prev_sibling_or_token() {
+            Some(it) => PositionRepr::After(it),
+            None => PositionRepr::FirstChild(elem.parent().unwrap()),
+        };
+        Position { repr }
+    }
+    pub fn first_child_of(node: &(impl Into<SyntaxNode> + Clone)) -> Position {
+        let repr = PositionRepr::FirstChild(node.clone().into());
+        Position { repr }
+    }
+    pub fn last_child_of(node: &(impl Into<SyntaxNode> + Clone)) -> Position {
+        let node = node.clone().into();
+        let repr = match node.last_child_or_token() {
+            Some(it) => PositionRepr::After(it),
+            None => PositionRepr::FirstChild(node),
+        };
+        Position { repr }
+    }
+
+    fn place(&self) -> (SyntaxNode, usize) {
+        match &self.repr {
+            PositionRepr::FirstChild(parent) => (parent.clone(), 0),
+            PositionRepr::After(child) => (child.parent().unwrap(), child.index() + 1),
+        }
+    }
+
+    fn prev(&self) -> Option<SyntaxElement> {
+        match &self.repr {
+            PositionRepr::FirstChild(_) => None,
+            PositionRepr::After(it) => Some(it.clone()),
+        }
+    }
+
+    fn next(&self) -> Option<SyntaxElement> {
+        match &self.repr {
+            PositionRepr::FirstChild(parent) => parent.first_child_or_token(),
+            PositionRepr::After(it) => it.next_sibling_or_token(),
+        }
+    }
+
+    /// The token right before the position, anywhere in the tree.
+    fn prev_token(&self) -> Option<SyntaxToken> {
+        match &self.repr {
+            PositionRepr::After(it) => last_token(it).or_else(|| prev_token(it)),
+            PositionRepr::FirstChild(parent) => prev_token(&parent.clone().into()),
+        }
+    }
+}
+
+pub fn insert(position: Position, elem: impl Element) {
+    insert_all(position, vec![elem.syntax_element()]);
+}
+pub fn insert_raw(position: Position, elem: impl Element) {
+    insert_all_raw(position, vec![elem.syntax_element()]);
+}
+
+/// Inserts `elements` at `position`.
+///
+/// Items are put on a line of their own: at the position if it starts a line,
+/// or on the next line, aligned with the item before them or one block
+/// deeper than the header that opens the block. Other elements stay on the
+/// line, separated by spaces where needed. Nodes that span several lines are
+/// re-indented to the column where they end up.
+pub fn insert_all(position: Position, mut elements: Vec<SyntaxElement>) {
+    let Some(first) = elements.first().cloned() else {
+        return;
+    };
+    let prev_token = position.prev_token();
+    let target = target_indent(&position, &first);
+    for elem in &elements {
+        move_to(elem, target);
+    }
+
+    if is_line(&first) {
+        if starts_line(prev_token.as_ref()) {
+            if position.next().is_some_and(|it| it.kind() != NEWLINE) {
+                elements.extend(line_break(target));
+            }
+        } else {
+            elements.splice(0..0, line_break(target));
+        }
+    } else {
+        if let Some(prev) = &prev_token
+            && needs_space(prev.kind(), first.kind())
+        {
+            elements.insert(0, make::tokens::single_space().into());
+        }
+        if let Some(next) = position.next().and_then(|it| first_token(&it))
+            && needs_space(elements.last().unwrap().kind(), next.kind())
+        {
+            elements.push(make::tokens::single_space().into());
+        }
+    }
+    insert_all_raw(position, elements);
+}
+
+pub fn insert_all_raw(position: Position, elements: Vec<SyntaxElement>) {
+    let (parent, index) = position.place();
+    parent.splice_children(index..index, elements);
+}
+
+/// Removes `elem`, together with its line if it is the only thing on it.
+pub fn remove(elem: impl Element) {
+    let elem = elem.syntax_element();
+    let indent = IndentLevel::from_element(&elem);
+    let prev = elem.prev_sibling_or_token();
+    let next = elem.next_sibling_or_token();
+    let alone = starts_line(prev_token(&elem).as_ref())
+        && next_token(&elem).is_none_or(|it| it.kind() == NEWLINE);
+
+    let mut to_remove = Vec::new();
+    if alone {
+        match line_start(&elem) {
+            // The line break before the element goes with it.
+            Some(start) => to_remove.extend(siblings(start..=prev.unwrap())),
+            None => to_remove.extend(next.filter(|it| it.kind() == NEWLINE)),
+        }
+    } else if let Some(prev) = prev.filter(|it| it.kind() == WHITESPACE)
+        && next
+            .as_ref()
+            .is_none_or(|it| matches!(it.kind(), WHITESPACE | NEWLINE))
+    {
+        to_remove.push(prev);
+    }
+    to_remove.push(elem.clone());
+    for it in to_remove {
+        remove_raw(it);
+    }
+    if let SyntaxElement::Node(node) = elem {
+        indent.decrease_indent(&node);
+    }
+}
+
+pub fn remove_raw(elem: impl Element) {
+    elem.syntax_element().detach();
+}
+
+pub fn remove_all(range: RangeInclusive<SyntaxElement>) {
+    replace_all(range, Vec::new());
+}
+
+/// Replaces `old` with `new`, re-indented to the column of `old`.
+pub fn replace(old: impl Element, new: impl Element) {
+    let old = old.syntax_element();
+    let new = new.syntax_element();
+    let target = IndentLevel::from_element(&old);
+    move_to(&new, target);
+    replace_all(old.clone()..=old, vec![new]);
+}
+
+pub fn replace_raw(old: impl Element, new: impl Element) {
+    let old = old.syntax_element();
+    replace_all(old.clone()..=old, vec![new.syntax_element()]);
+}
+
+pub fn replace_with_many(old: impl Element, new: Vec<SyntaxElement>) {
+    let old = old.syntax_element();
+    replace_all(old.clone()..=old, new);
+}
+
+pub fn replace_all(range: RangeInclusive<SyntaxElement>, new: Vec<SyntaxElement>) {
+    let start = range.start().index();
+    let end = range.end().index();
+    let parent = range.start().parent().unwrap();
+    parent.splice_children(start..end + 1, new);
+}
+
+pub fn append_child(node: &(impl Into<SyntaxNode> + Clone), child: impl Element) {
+    let position = Position::last_child_of(node);
+    insert(position, child);
+}
+pub fn append_child_raw(node: &(impl Into<SyntaxNode> + Clone), child: impl Element) {
+    let position = Position::last_child_of(node);
+    insert_raw(position, child);
+}
+
+/// The indentation that an element inserted at `position` should have.
+fn target_indent(position: &Position, elem: &SyntaxElement) -> IndentLevel {
+    let prev_token = position.prev_token();
+    if starts_line(prev_token.as_ref()) {
+        return match prev_token {
+            Some(it) if it.kind() == WHITESPACE => IndentLevel(it.text().len()),
+            _ => IndentLevel(0),
+        };
+    }
+    let Some(prev_token) = prev_token else {
+        return IndentLevel(0);
+    };
+    if !is_line(elem) {
+        // The element stays on the line, after the previous token.
+        let space = needs_space(prev_token.kind(), elem.kind()) as usize;
+        let end = IndentLevel::column_of(&prev_token).0 + prev_token.text().len();
+        return IndentLevel(end + space);
+    }
+    let line = IndentLevel::from_token(&prev_token);
+    match position.prev() {
+        Some(prev) if is_line(&prev) => IndentLevel::from_element(&prev),
+        _ => line + IndentLevel::BLOCK,
+    }
+}
+
+/// Detaches `elem` if it is a part of a tree, and re-indents its lines from
+/// the column where it was to `target`.
+fn move_to(elem: &SyntaxElement, target: IndentLevel) {
+    let current = IndentLevel::from_element(elem);
+    if elem.parent().is_some() {
+        elem.detach();
+    }
+    if let SyntaxElement::Node(node) = elem {
+        match current.cmp(&target) {
+            std::cmp::Ordering::Less => IndentLevel(target.0 - current.0).increase_indent(node),
+            std::cmp::Ordering::Greater => IndentLevel(current.0 - target.0).decrease_indent(node),
+            std::cmp::Ordering::Equal => (),
+        }
+    }
+}
+
+/// Whether the element goes on a line of its own.
+fn is_line(elem: &SyntaxElement) -> bool {
+    ast::Item::can_cast(elem.kind())
+}
+
+/// Whether the position right after `prev` is at the start of a line, not
+/// counting the indentation.
+fn starts_line(prev: Option<&SyntaxToken>) -> bool {
+    match prev {
+        None => true,
+        Some(it) if it.kind() == NEWLINE => true,
+        Some(it) if it.kind() == WHITESPACE => {
+            it.prev_token().is_none_or(|it| it.kind() == NEWLINE)
+        },
+        Some(_) => false,
+    }
+}
+
+/// The sibling `NEWLINE` that starts the line of `elem`, if the line break
+/// and the indentation are siblings of `elem`.
+fn line_start(elem: &SyntaxElement) -> Option<SyntaxElement> {
+    let prev = elem.prev_sibling_or_token()?;
+    match prev.kind() {
+        NEWLINE => Some(prev),
+        WHITESPACE => {
+            prev.prev_sibling_or_token()
+                .filter(|it| it.kind() == NEWLINE)
+        },
+        _ => None,
+    }
+}
+
+fn siblings(range: RangeInclusive<SyntaxElement>) -> Vec<SyntaxElement> {
+    let (start, end) = range.into_inner();
+    let mut res = vec![start.clone()];
+    let mut it = start;
+    while it != end {
+        it = it.next_sibling_or_token().unwrap();
+        res.push(it.clone());
+    }
+    res
+}
+
+fn line_break(indent: IndentLevel) -> Vec<SyntaxElement> {
+    let mut res = vec![make::tokens::newline().into()];
+    if indent.0 > 0 {
+        res.push(make::tokens::whitespace(&indent.to_string()).into());
+    }
+    res
+}
+
+fn needs_space(left: SyntaxKind, right: SyntaxKind) -> bool {
+    let trivia = |kind: SyntaxKind| kind.is_trivia();
+    let no_space_after = [T!['('], T!['['], T![.]];
+    let no_space_before = [T![')'], T![']'], T![,], T![.]];
+    !(trivia(left)
+        || trivia(right)
+        || no_space_after.contains(&left)
+        || no_space_before.contains(&right))
+}
+
+fn first_token(elem: &SyntaxElement) -> Option<SyntaxToken> {
+    match elem {
+        SyntaxElement::Node(it) => it.first_token(),
+        SyntaxElement::Token(it) => Some(it.clone()),
+    }
+}
+
+fn last_token(elem: &SyntaxElement) -> Option<SyntaxToken> {
+    match elem {
+        SyntaxElement::Node(it) => it.last_token(),
+        SyntaxElement::Token(it) => Some(it.clone()),
+    }
+}
+
+fn prev_token(elem: &SyntaxElement) -> Option<SyntaxToken> {
+    match elem {
+        SyntaxElement::Node(it) => {
+            match it.first_token() {
+                Some(first) => first.prev_token(),
+                None => it.prev_sibling_or_token().and_then(|it| last_token(&it)),
+            }
+        },
+        SyntaxElement::Token(it) => it.prev_token(),
+    }
+}
+
+fn next_token(elem: &SyntaxElement) -> Option<SyntaxToken> {
+    match elem {
+        SyntaxElement::Node(it) => it.last_token().and_then(|it| it.next_token()),
+        SyntaxElement::Token(it) => it.next_token(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceFile;
+
+    /// Applies `edit` to a mutable tree of `before`, and checks that the result
+    /// is `after` and still parses without errors.
+    #[track_caller]
+    fn check(before: &str, edit: impl FnOnce(&SyntaxNode), after: &str) {
+        let file = SourceFile::parse(before).tree().syntax().clone_for_update();
+        edit(&file);
+        let text = file.to_string();
+        assert_eq!(text, after);
+        let errors = SourceFile::parse(&text).errors();
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    fn find<N: AstNode>(root: &SyntaxNode, nth: usize) -> N {
+        root.descendants().filter_map(N::cast).nth(nth).unwrap()
+    }
+
+    fn let_f() -> SyntaxNode {
+        let body = make::expr_from_text("match x with\n| A -> 1\n| B -> 2");
+        let decl = make::let_decl(make::name("f"), None, body);
+        decl.syntax().clone_for_update()
+    }
+
+    #[test]
+    fn append_item_to_module() {
+        check(
+            "module M =\n    let a\nlet b",
+            |file| append_child(find::<ast::Module>(file, 0).syntax(), let_f()),
+            "module M =\n    let a\n    let f =\n        match x with\n        | A -> 1\n        | B -> 2\nlet b",
+        );
+    }
+
+    #[test]
+    fn move_item_out_of_module() {
+        check(
+            "module M =\n    let a =\n        f x\n    let c\nlet b",
+            |file| {
+                let a = find::<ast::LetDecl>(file, 0);
+                let b = find::<ast::LetDecl>(file, 2);
+                remove(a.syntax());
+                insert(Position::before(b.syntax()), a.syntax());
+            },
+            "module M =\n    let c\nlet a =\n    f x\nlet b",
+        );
+        check(
+            "let a\nlet b =\n    g",
+            |file| {
+                let b = find::<ast::LetDecl>(file, 1);
+                let a = find::<ast::LetDecl>(file, 0);
+                remove(b.syntax());
+                let m = make::module(make::name("M"), [make::item_from_text("let x")]);
+                let m = m.syntax().clone_for_update();
+                insert(Position::after(a.syntax()), &m);
+                append_child(&m, b.syntax());
+            },
+            "let a\nmodule M =\n    let x\n    let b =\n        g",
+        );
+    }
+
+    #[test]
+    fn replace_reindents() {
+        check(
+            "module M =\n    let f =\n        x\n",
+            |file| {
+                let x = find::<ast::PathExpr>(file, 0);
+                let body = make::expr_from_text("match x with\n| A -> 1\n| B -> 2");
+                replace(x.syntax(), body.syntax().clone_for_update());
+            },
+            "module M =\n    let f =\n        match x with\n        | A -> 1\n        | B -> 2\n",
+        );
+    }
+
+    #[test]
+    fn replace_mid_line() {
+        // The cases go under `match`, not under `let`.
+        check(
+            "module M =\n    let f = x\n",
+            |file| {
+                let x = find::<ast::PathExpr>(file, 0);
+                let body = make::expr_from_text("match x with\n| A -> 1\n| B -> 2");
+                replace(x.syntax(), body.syntax().clone_for_update());
+            },
+            "module M =\n    let f = match x with\n            | A -> 1\n            | B -> 2\n",
+        );
+        // And back, to the start of a line.
+        check(
+            "let f =\n    g (match x with\n       | A -> 1)\n",
+            |file| {
+                let m = find::<ast::MatchExpr>(file, 0);
+                let app = find::<ast::AppExpr>(file, 0);
+                remove(m.syntax());
+                replace(app.syntax(), m.syntax());
+            },
+            "let f =\n    match x with\n    | A -> 1\n",
+        );
+    }
+
+    #[test]
+    fn remove_lines() {
+        check(
+            "module M =\n    let a\n    let b\nlet c\n",
+            |file| {
+                remove(find::<ast::LetDecl>(file, 0).syntax());
+                remove(find::<ast::LetDecl>(file, 1).syntax());
+            },
+            "module M =\n    let b\n",
+        );
+        check(
+            "let a\nlet b",
+            |file| remove(find::<ast::LetDecl>(file, 0).syntax()),
+            "let b",
+        );
+    }
+
+    #[test]
+    fn inline_insert() {
+        check(
+            "let f x = x",
+            |file| {
+                let params = find::<ast::ParamList>(file, 0);
+                let y = make::ident_pat(make::name("y"));
+                append_child(params.syntax(), y.syntax().clone_for_update());
+            },
+            "let f x y = x",
+        );
+    }
+}
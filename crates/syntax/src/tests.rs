@@ -0,0 +1,28 @@
+use std::{fs, path::Path};
+
+use expect_test::expect_file;
+
+use crate::SourceFile;
+
+/// Each file of `test_data/dump` is dumped next to itself, with
+/// [`crate::Parse::debug_dump`] into `.rast` and with
+/// [`crate::Parse::debug_dump_compact`] into `.compact.rast`. Run with
+/// `UPDATE_EXPECT=1` to update the dumps.
+#[test]
+fn dump_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/dump");
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("can't read {}: {err}", dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().unwrap_or_default() == "fsic")
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "no fixtures in {}", dir.display());
+
+    for path in files {
+        let text = fs::read_to_string(&path).unwrap();
+        let parse = SourceFile::parse(&text);
+        expect_file![path.with_extension("rast")].assert_eq(&parse.debug_dump());
+        expect_file![path.with_extension("compact.rast")].assert_eq(&parse.debug_dump_compact());
+    }
+}
@@ -0,0 +1,325 @@
+//! Changes to a text, as made by an editor.
+
+use crate::{TextRange, TextSize};
+
+/// A single change of a text: the `delete` range is replaced with `insert`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Indel {
+    pub insert: String,
+    /// Refers to offsets in the original text.
+    pub delete: TextRange,
+}
+
+impl Indel {
+    pub fn insert(offset: TextSize, text: String) -> Indel {
+        Indel::replace(TextRange::empty(offset), text)
+    }
+
+    pub fn delete(range: TextRange) -> Indel {
+        Indel::replace(range, String::new())
+    }
+
+    pub fn replace(range: TextRange, replace_with: String) -> Indel {
+        Indel {
+            delete: range,
+            insert: replace_with,
+        }
+    }
+
+    pub fn apply(&self, text: &mut String) {
+        let start: usize = self.delete.start().into();
+        let end: usize = self.delete.end().into();
+        text.replace_range(start..end, &self.insert);
+    }
+}
+
+/// A set of disjoint [`Indel`]s that are applied at once, as the offsets of
+/// all of them are in the original text.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    /// Sorted and disjoint.
+    indels: Vec<Indel>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TextEditBuilder {
+    indels: Vec<Indel>,
+}
+
+impl TextEdit {
+    pub fn builder() -> TextEditBuilder {
+        TextEditBuilder::default()
+    }
+
+    pub fn insert(offset: TextSize, text: String) -> TextEdit {
+        TextEdit {
+            indels: vec![Indel::insert(offset, text)],
+        }
+    }
+
+    pub fn delete(range: TextRange) -> TextEdit {
+        TextEdit {
+            indels: vec![Indel::delete(range)],
+        }
+    }
+
+    pub fn replace(range: TextRange, replace_with: String) -> TextEdit {
+        TextEdit {
+            indels: vec![Indel::replace(range, replace_with)],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.indels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indels.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Indel> {
+        self.indels.iter()
+    }
+
+    pub fn apply(&self, text: &mut String) {
+        // Back to front, so that the offsets of the rest stay valid.
+        for indel in self.indels.iter().rev() {
+            indel.apply(text);
+        }
+    }
+
+    /// The edit that does `self` and then `next`, whose offsets are in the
+    /// text edited by `self`.
+    pub fn compose(&self, next: &TextEdit) -> TextEdit {
+        // The text after both edits, as pieces of the original text and
+        // inserted texts.
+        let edited = next.pieces_of(&self.pieces());
+        let mut builder = TextEdit::builder();
+        // The end of the original text that was kept last, and what was
+        // inserted since.
+        let mut kept = TextSize::from(0);
+        let mut inserted = String::new();
+        for piece in edited {
+            match piece {
+                Piece::Insert(text) => inserted.push_str(&text),
+                Piece::Keep(range) => {
+                    if range.start() != kept || !inserted.is_empty() {
+                        let deleted = TextRange::new(kept, range.start());
+                        builder.replace(deleted, std::mem::take(&mut inserted));
+                    }
+                    kept = range.end();
+                },
+            }
+        }
+        builder.finish()
+    }
+
+    /// Maps an offset in the original text to the edited one. Returns `None`
+    /// if the offset is in a deleted range.
+    pub fn apply_to_offset(&self, offset: TextSize) -> Option<TextSize> {
+        let mut res = offset;
+        for indel in &self.indels {
+            if indel.delete.start() >= offset {
+                break;
+            }
+            if offset < indel.delete.end() {
+                return None;
+            }
+            res += TextSize::of(&indel.insert);
+            res -= indel.delete.len();
+        }
+        Some(res)
+    }
+
+    /// The edited text, as pieces of the original text and inserted texts.
+    /// The last piece keeps the rest of the original text, up to [`REST`].
+    fn pieces(&self) -> Vec<Piece> {
+        let mut res = Vec::new();
+        let mut kept = TextSize::from(0);
+        for indel in &self.indels {
+            res.push(Piece::Keep(TextRange::new(kept, indel.delete.start())));
+            res.push(Piece::Insert(indel.insert.clone()));
+            kept = indel.delete.end();
+        }
+        res.push(Piece::Keep(TextRange::new(kept, REST)));
+        res.retain(|it| !it.is_empty());
+        res
+    }
+
+    /// Applies `self` to a text made of `pieces`.
+    fn pieces_of(&self, pieces: &[Piece]) -> Vec<Piece> {
+        let mut res = Vec::new();
+        let mut kept = TextSize::from(0);
+        for indel in &self.indels {
+            res.extend(slice(pieces, TextRange::new(kept, indel.delete.start())));
+            res.push(Piece::Insert(indel.insert.clone()));
+            kept = indel.delete.end();
+        }
+        res.extend(slice(pieces, TextRange::new(kept, REST)));
+        res.retain(|it| !it.is_empty());
+        res
+    }
+}
+
+impl IntoIterator for TextEdit {
+    type Item = Indel;
+    type IntoIter = std::vec::IntoIter<Indel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.indels.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a TextEdit {
+    type Item = &'a Indel;
+    type IntoIter = std::slice::Iter<'a, Indel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.indels.iter()
+    }
+}
+
+impl TextEditBuilder {
+    pub fn is_empty(&self) -> bool {
+        self.indels.is_empty()
+    }
+
+    pub fn insert(&mut self, offset: TextSize, text: String) {
+        self.indel(Indel::insert(offset, text));
+    }
+
+    pub fn delete(&mut self, range: TextRange) {
+        self.indel(Indel::delete(range));
+    }
+
+    pub fn replace(&mut self, range: TextRange, replace_with: String) {
+        self.indel(Indel::replace(range, replace_with));
+    }
+
+    pub fn indel(&mut self, indel: Indel) {
+        if indel.delete.is_empty() && indel.insert.is_empty() {
+            return;
+        }
+        self.indels.push(indel);
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the edits overlap, or if there are several insertions at
+    /// one offset, as their order would be unclear.
+    pub fn finish(self) -> TextEdit {
+        let mut indels = self.indels;
+        indels.sort_by_key(|it| (it.delete.start(), it.delete.end()));
+        for pair in indels.windows(2) {
+            assert!(
+                pair[0].delete.end() <= pair[1].delete.start() && pair[0].delete != pair[1].delete,
+                "overlapping edits: {:?} and {:?}",
+                pair[0],
+                pair[1],
+            );
+        }
+        TextEdit { indels }
+    }
+}
+
+/// Stands for the end of the original text, which edits don't know. Far from
+/// `u32::MAX`, so that offsets past it don't overflow.
+const REST: TextSize = TextSize::new(u32::MAX / 2);
+
+/// A part of an edited text.
+#[derive(Debug, Clone)]
+enum Piece {
+    /// A range of the original text.
+    Keep(TextRange),
+    Insert(String),
+}
+
+impl Piece {
+    fn len(&self) -> TextSize {
+        match self {
+            Piece::Keep(range) => range.len(),
+            Piece::Insert(text) => TextSize::of(text.as_str()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == TextSize::from(0)
+    }
+}
+
+/// The pieces of the text made of `pieces` that are in `range` of it.
+fn slice(pieces: &[Piece], range: TextRange) -> Vec<Piece> {
+    let mut res = Vec::new();
+    let mut start = TextSize::from(0);
+    for piece in pieces {
+        let piece_range = TextRange::at(start, piece.len());
+        start = piece_range.end();
+        let Some(common) = piece_range.intersect(range) else {
+            continue;
+        };
+        // Relative to the start of the piece.
+        let common = common - piece_range.start();
+        res.push(match piece {
+            Piece::Keep(kept) => Piece::Keep(common + kept.start()),
+            Piece::Insert(text) => Piece::Insert(text[common].to_owned()),
+        });
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(indels: &[(u32, u32, &str)]) -> TextEdit {
+        let mut builder = TextEdit::builder();
+        for &(start, end, text) in indels {
+            builder.replace(TextRange::new(start.into(), end.into()), text.to_owned());
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn compose() {
+        let text = "let x = 1 + 2";
+        let cases = [
+            (edit(&[(4, 5, "y")]), edit(&[(8, 9, "10")])),
+            (
+                edit(&[(8, 13, "3")]),
+                edit(&[(8, 9, "4 * 5"), (0, 0, "// hi\n")]),
+            ),
+            (
+                edit(&[(0, 0, "ab"), (13, 13, "cd")]),
+                edit(&[(1, 4, ""), (15, 17, "!")]),
+            ),
+            (edit(&[(4, 5, "long_name")]), edit(&[(6, 12, "")])),
+            (TextEdit::default(), edit(&[(0, 3, "val")])),
+        ];
+        for (first, second) in cases {
+            let mut expected = text.to_owned();
+            first.apply(&mut expected);
+            second.apply(&mut expected);
+
+            let composed = first.compose(&second);
+            let mut actual = text.to_owned();
+            composed.apply(&mut actual);
+            assert_eq!(actual, expected, "{composed:?}");
+        }
+
+        // Edits of the same place become one edit of the original text.
+        let composed = edit(&[(4, 5, "yy")]).compose(&edit(&[(5, 6, "z")]));
+        assert_eq!(composed, edit(&[(4, 5, "yz")]));
+    }
+
+    #[test]
+    fn apply_to_offset() {
+        let edit = edit(&[(2, 4, "abc"), (6, 6, "d")]);
+        let map = |offset: u32| edit.apply_to_offset(offset.into()).map(u32::from);
+        assert_eq!(map(1), Some(1));
+        assert_eq!(map(2), Some(2));
+        assert_eq!(map(3), None);
+        assert_eq!(map(4), Some(5));
+        assert_eq!(map(6), Some(7));
+        assert_eq!(map(7), Some(9));
+    }
+}
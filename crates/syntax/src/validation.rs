@@ -0,0 +1,369 @@
+//! Checks of the syntax tree for errors that the grammar can't express, or
+//! that the parser accepts to keep the tree regular.
+//!
+//! Validation runs on any tree, including the ones built by editing, so it
+//! doesn't assume that the tree came from the parser.
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+    AstNode, ErrorCode, SyntaxError,
+    SyntaxKind::{self, *},
+    SyntaxNode, SyntaxToken, T, TextRange, TextSize,
+    ast::{self, AstToken, HasName, HasParamList, HasTypeVars, LiteralErrorKind},
+};
+
+pub(crate) fn validate(root: &SyntaxNode, errors: &mut Vec<SyntaxError>) {
+    for node in root.descendants() {
+        match node.kind() {
+            LET_DECL => validate_let_rec(ast::LetDecl::cast(node.clone()).unwrap(), errors),
+            LET_OP => validate_let_op(ast::LetOp::cast(node.clone()).unwrap(), errors),
+            UNION => validate_union(ast::Union::cast(node.clone()).unwrap(), errors),
+            CLASS => validate_class(ast::Class::cast(node.clone()).unwrap(), errors),
+            INFER_TYPE => validate_infer_type(&node, errors),
+            LITERAL => validate_literal(ast::Literal::cast(node.clone()).unwrap(), errors),
+            _ => (),
+        }
+        validate_attrs(&node, errors);
+    }
+}
+
+/// Only functions can be recursive: a name, or an operator, with parameters.
+fn validate_let_rec(let_decl: ast::LetDecl, errors: &mut Vec<SyntaxError>) {
+    let Some(rec) = let_decl.rec_token() else {
+        return;
+    };
+    let target = match (let_decl.let_op(), let_decl.pat()) {
+        (Some(op), _) => op.syntax().clone(),
+        (None, Some(pat)) => pat.syntax().clone(),
+        (None, None) => return,
+    };
+    let is_name = target.kind() == LET_OP
+        || ast::IdentPat::cast(target.clone()).is_some_and(|it| it.pat().is_none());
+    if is_name && let_decl.param_list().is_some() {
+        return;
+    }
+    errors.push(
+        SyntaxError::new(
+            ErrorCode::RecNonFunction,
+            "only functions can be recursive",
+            target.text_range(),
+        )
+        .with_label(rec.text_range(), "declared recursive here")
+        .with_note("a recursive function needs a name and parameters, as in `let rec f x = ...`"),
+    );
+}
+
+const OPERATORS: [SyntaxKind; 14] = [
+    T![*],
+    T![/],
+    T![%],
+    T![+],
+    T![-],
+    T![::],
+    T![=],
+    T![<>],
+    T![<],
+    T![>],
+    T![<=],
+    T![>=],
+    T![&&],
+    T![||],
+];
+
+fn validate_let_op(let_op: ast::LetOp, errors: &mut Vec<SyntaxError>) {
+    let token = let_op.operator().and_then(|it| it.syntax().first_token());
+    if token.is_some_and(|it| OPERATORS.contains(&it.kind())) {
+        return;
+    }
+    errors.push(SyntaxError::new(
+        ErrorCode::UnknownOperator,
+        "unknown operator",
+        let_op.syntax().text_range(),
+    ));
+}
+
+fn validate_union(union: ast::Union, errors: &mut Vec<SyntaxError>) {
+    if union.eq_token().is_none() || union.cases().next().is_some() {
+        return;
+    }
+    let range = match union.name() {
+        Some(name) => name.syntax().text_range(),
+        None => union.syntax().text_range(),
+    };
+    errors.push(SyntaxError::new(
+        ErrorCode::EmptyUnion,
+        "a union needs at least one case",
+        range,
+    ));
+}
+
+fn validate_class(class: ast::Class, errors: &mut Vec<SyntaxError>) {
+    if class.type_vars().next().is_some() {
+        return;
+    }
+    let Some(name) = class.name() else {
+        return;
+    };
+    errors.push(SyntaxError::new(
+        ErrorCode::NoTypeVars,
+        "a class needs at least one type variable",
+        name.syntax().text_range(),
+    ));
+}
+
+/// Types of items are spelled out, only expressions, including the local
+/// `let`s in them, may leave a type to inference.
+fn validate_infer_type(node: &SyntaxNode, errors: &mut Vec<SyntaxError>) {
+    for ancestor in node.ancestors().skip(1) {
+        let is_local_let = ancestor.kind() == LET_DECL
+            && ancestor.parent().is_some_and(|it| it.kind() == LET_EXPR);
+        if is_local_let || ast::Expr::can_cast(ancestor.kind()) {
+            return;
+        }
+        if ast::Item::can_cast(ancestor.kind()) {
+            errors.push(SyntaxError::new(
+                ErrorCode::InferTypeInSignature,
+                "`_` is not allowed in the signature of an item",
+                node.text_range(),
+            ));
+            return;
+        }
+    }
+}
+
+/// Integers are of the type of their suffix, `i64` without one. The literal of
+/// a signed integer may be one past the largest integer if it is negated.
+fn validate_literal(literal: ast::Literal, errors: &mut Vec<SyntaxError>) {
+    let int = ast::IntNumber::cast(literal.token());
+    // The largest magnitude the literal may have, with a note that tells it.
+    let limit = int.as_ref().and_then(|int| {
+        let (min, max) = int_bounds(int.suffix())?;
+        // Only signed integers have a smallest value past the largest one.
+        let negated = min > 0 && literal.syntax().parent().is_some_and(|it| is_negation(&it));
+        Some(match int.suffix() {
+            Some(suffix) if negated => (min, format!("the smallest `{suffix}` is -{min}")),
+            Some(suffix) => (max, format!("the largest `{suffix}` is {max}")),
+            None if negated => (min, format!("the smallest integer is -{min}")),
+            None => (max, format!("the largest integer is {max}")),
+        })
+    });
+    let overflow = |range| {
+        let error = SyntaxError::new(
+            ErrorCode::IntOverflow,
+            "integer literal is too large",
+            range,
+        );
+        match &limit {
+            Some((_, note)) => error.with_note(note.clone()),
+            None => error,
+        }
+    };
+    for error in literal.errors() {
+        errors.push(match error.kind {
+            LiteralErrorKind::IntOverflow => overflow(error.range),
+            kind => SyntaxError::new(ErrorCode::InvalidLiteral, kind.to_string(), error.range),
+        });
+    }
+
+    if let Some(int) = int
+        && let Some((limit, _)) = limit
+        && let Ok(value) = int.value()
+        && value > limit
+    {
+        let (digits, _) = int.split_into_parts();
+        let start = int.syntax().text_range().start();
+        errors.push(overflow(TextRange::at(start, TextSize::of(digits))));
+    }
+}
+
+/// The magnitudes of the smallest and of the largest integer with `suffix`,
+/// or `None` for a suffix that is not an integer type. The smallest unsigned
+/// integer is 0.
+fn int_bounds(suffix: Option<&str>) -> Option<(u128, u128)> {
+    let (min, max) = match suffix {
+        Some("i8") => (i8::MIN.unsigned_abs().into(), i8::MAX as u128),
+        Some("i16") => (i16::MIN.unsigned_abs().into(), i16::MAX as u128),
+        Some("i32") => (i32::MIN.unsigned_abs().into(), i32::MAX as u128),
+        None | Some("i64") => (i64::MIN.unsigned_abs().into(), i64::MAX as u128),
+        Some("i128") => (i128::MIN.unsigned_abs(), i128::MAX as u128),
+        Some("u8") => (0, u8::MAX.into()),
+        Some("u16") => (0, u16::MAX.into()),
+        Some("u32") => (0, u32::MAX.into()),
+        Some("u64") => (0, u64::MAX.into()),
+        Some("u128") => (0, u128::MAX),
+        Some(_) => return None,
+    };
+    Some((min, max))
+}
+
+fn is_negation(node: &SyntaxNode) -> bool {
+    node.kind() == UNARY_EXPR
+        && node
+            .children()
+            .find(|it| it.kind() == OPERATOR)
+            .and_then(|it| it.first_token())
+            .is_some_and(|it| it.kind() == T![-])
+}
+
+fn validate_attrs(node: &SyntaxNode, errors: &mut Vec<SyntaxError>) {
+    let mut seen: FxHashMap<String, SyntaxToken> = FxHashMap::default();
+    let attrs = node.children().filter(|it| it.kind() == ATTR);
+    for token in attrs.filter_map(|it| it.first_token()) {
+        let name = attr_name(token.text()).to_owned();
+        match seen.get(&name) {
+            Some(first) => {
+                errors.push(
+                    SyntaxError::new(
+                        ErrorCode::DuplicateAttr,
+                        format!("duplicate attribute `{name}`"),
+                        token.text_range(),
+                    )
+                    .with_label(first.text_range(), "first applied here"),
+                );
+            },
+            None => {
+                seen.insert(name, token);
+            },
+        }
+    }
+}
+
+/// The name of the attribute in `[<Name>]`.
+fn attr_name(text: &str) -> &str {
+    let text = text.strip_prefix("[<").unwrap_or(text);
+    text.strip_suffix(">]").unwrap_or(text).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SourceFile, SyntaxTreeBuilder};
+
+    /// Checks the validation errors of `text`, rendered as `code start..end:
+    /// message`, one per line.
+    #[track_caller]
+    fn check(text: &str, expected: &str) {
+        let parse = SourceFile::parse(text);
+        let mut errors = Vec::new();
+        validate(&parse.syntax_node(), &mut errors);
+        let actual: Vec<String> = errors
+            .iter()
+            .map(|err| {
+                let range = err.range();
+                let (start, end) = (u32::from(range.start()), u32::from(range.end()));
+                format!("{} {start}..{end}: {}", err.code().as_str(), err.message())
+            })
+            .collect();
+        assert_eq!(actual.join("\n"), expected.trim());
+    }
+
+    #[test]
+    fn let_rec() {
+        check("let rec f x = f x\nlet rec (+) x y = x", "");
+        check(
+            "let rec (a, b) = p",
+            "E0008 8..14: only functions can be recursive",
+        );
+        check(
+            "let rec x = 1",
+            "E0008 8..9: only functions can be recursive",
+        );
+    }
+
+    #[test]
+    fn let_op() {
+        check("let (<>) x y = x", "");
+
+        // The parser only takes operators, an edit may put anything there.
+        let mut builder = SyntaxTreeBuilder::default();
+        builder.start_node(LET_OP);
+        builder.token(T!['('], "(");
+        builder.start_node(OPERATOR);
+        builder.token(IDENT, "plus");
+        builder.finish_node();
+        builder.token(T![')'], ")");
+        builder.finish_node();
+        let mut errors = Vec::new();
+        validate(&builder.finish().syntax_node(), &mut errors);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), ErrorCode::UnknownOperator);
+    }
+
+    #[test]
+    fn duplicate_attrs() {
+        check(
+            "[<A>] [<B>] [< A >]\nlet x",
+            "E0009 12..19: duplicate attribute `A`",
+        );
+        check("[<A>]\nmodule M =\n    [<A>]\n    let x", "");
+    }
+
+    #[test]
+    fn int_overflow() {
+        check("let x = 9223372036854775807 + -9223372036854775808", "");
+        check(
+            "let x = 9223372036854775808\nlet y = 99999999999999999999",
+            "E0010 8..27: integer literal is too large\nE0010 36..56: integer literal is too large",
+        );
+    }
+
+    #[test]
+    fn int_suffix() {
+        check(
+            "let x = 255u8 + -128i8 + 18446744073709551615u64 + -2u128",
+            "",
+        );
+        check(
+            "let x = 300u8 + -129i8 + 128i8 + 92foo",
+            "
+E0010 8..11: integer literal is too large
+E0010 17..20: integer literal is too large
+E0010 25..28: integer literal is too large
+E0015 35..38: invalid suffix for an integer literal",
+        );
+    }
+
+    #[test]
+    fn invalid_literals() {
+        check(r#"let x = "a\tb" + f"{x} \{y\}" + '\'' + 1u8"#, "");
+        check(
+            r#"let x = "a\qb\w" + f"{} {x" + f"a}" + 340282366920938463463374607431768211456"#,
+            r#"
+E0015 10..12: unknown character escape
+E0015 13..15: unknown character escape
+E0015 21..23: empty hole in f-string
+E0015 24..26: unclosed hole in f-string
+E0015 33..34: unmatched `}` in f-string
+E0010 38..77: integer literal is too large"#,
+        );
+    }
+
+    #[test]
+    fn empty_union() {
+        check("union A = B", "");
+        check(
+            "union A =\nlet x",
+            "E0011 6..7: a union needs at least one case",
+        );
+    }
+
+    #[test]
+    fn class_without_type_vars() {
+        check(
+            "class Eq =\n    let eq",
+            "E0013 6..8: a class needs at least one type variable",
+        );
+    }
+
+    #[test]
+    fn infer_type() {
+        check("let f x = (x : _)\nlet g = let h (y : _) = y in h", "");
+        check(
+            "let f (x : _) : Option[_] = x\nvalue V (_)",
+            "E0014 11..12: `_` is not allowed in the signature of an item
+E0014 23..24: `_` is not allowed in the signature of an item
+E0014 39..40: `_` is not allowed in the signature of an item",
+        );
+    }
+}
@@ -34,6 +34,8 @@ pub enum CodegenMode {
     All,
     /// Run grammar codegen.
     Grammar,
+    /// Extract the `// test` snippets of the parser into its test corpus.
+    ParserTests,
 }
 
 impl fmt::Display for CodegenMode {
@@ -41,6 +43,7 @@ impl fmt::Display for CodegenMode {
         match self {
             CodegenMode::All => write!(f, "all"),
             CodegenMode::Grammar => write!(f, "grammar"),
+            CodegenMode::ParserTests => write!(f, "parser-tests"),
         }
     }
 }
@@ -18,17 +18,17 @@ pub(crate) const PUNCT: &[(&str, &str)] = &[
     ("}", "R_CURLY"),
     ("[", "L_BRACKET"),
     ("]", "R_BRACKET"),
-    // ("<", "L_ANGLE"),
-    // (">", "R_ANGLE"),
+    ("<", "L_ANGLE"),
+    (">", "R_ANGLE"),
     // ("~", "TILDE"),
     // ("?", "QUESTION"),
     // ("&", "AMP"),
     ("|", "PIPE"),
-    // ("+", "PLUS"),
+    ("+", "PLUS"),
     ("*", "STAR"),
-    // ("/", "SLASH"),
+    ("/", "SLASH"),
     // ("^", "CARET"),
-    // ("%", "PERCENT"),
+    ("%", "PERCENT"),
     ("_", "UNDERSCORE"),
     (".", "DOT"),
     // ("...", "DOT3"),
@@ -37,11 +37,11 @@ pub(crate) const PUNCT: &[(&str, &str)] = &[
     ("=", "EQ"),
     // ("==", "EQ2"),
     // ("!", "BANG"),
-    // ("<>", "NEQ"),
-    // ("-", "MINUS"),
+    ("<>", "NEQ"),
+    ("-", "MINUS"),
     ("->", "ARROW"),
-    // ("<=", "LTEQ"),
-    // (">=", "GTEQ"),
+    ("<=", "LTEQ"),
+    (">=", "GTEQ"),
     // ("++", "PLUS2"),
     // ("--", "MINUS2"),
     // ("+=", "PLUSEQ"),
@@ -52,8 +52,10 @@ pub(crate) const PUNCT: &[(&str, &str)] = &[
     // ("/=", "SLASHEQ"),
     // ("*=", "STAREQ"),
     // ("%=", "PERCENTEQ"),
-    // ("&&", "AMP2"),
-    // ("||", "PIPE2"),
+    ("&&", "AMP2"),
+    ("||", "PIPE2"),
+    ("{|", "L_CURLY_PIPE"),
+    ("|}", "PIPE_R_CURLY"),
     // ("<<", "SHL"),
     // (">>", "SHR"),
     // ("<<=", "SHLEQ"),
@@ -97,13 +99,9 @@ pub(crate) fn generate_kind_src(
             },
         }
     });
-    PUNCT
-        .iter()
-        .zip(used_puncts)
-        .filter(|(_, used)| !used)
-        .for_each(|((punct, _), _)| {
-            panic!("Punctuation {punct:?} is not used in grammar");
-        });
+    if let Some(((punct, _), _)) = PUNCT.iter().zip(used_puncts).find(|(_, used)| !used) {
+        panic!("Punctuation {punct:?} is not used in grammar");
+    }
     keywords.sort();
     keywords.dedup();
 
@@ -106,6 +106,7 @@ fn generate_nodes(kinds: KindsSrc, grammar: &AstSrc) -> String {
                     node.name != "ForExpr" && node.name != "WhileExpr"
                         || trait_name.as_str() != "HasLoopBody"
                 })
+                .filter(|trait_name| !is_manual_impl(&node.name, trait_name))
                 .map(|trait_name| {
                     let trait_name = format_ident!("{}", trait_name);
                     quote!(impl ast::#trait_name for #name {})
@@ -250,6 +251,8 @@ fn generate_nodes(kinds: KindsSrc, grammar: &AstSrc) -> String {
                 .map(|name| format_ident!("{}", to_upper_snake_case(&name.name.to_string())))
                 .collect();
             let nodes = nodes.iter().map(|node| format_ident!("{}", node.name));
+            let trait_impl = (!is_manual_impl(&name.to_string(), &trait_name.to_string()))
+                .then(|| quote!(impl ast::#trait_name for #name {}));
             (
                 quote! {
                     #[pretty_doc_comment_placeholder_workaround]
@@ -257,7 +260,7 @@ fn generate_nodes(kinds: KindsSrc, grammar: &AstSrc) -> String {
                     pub struct #name {
                         pub(crate) syntax: SyntaxNode,
                     }
-                    impl ast::#trait_name for #name {}
+                    #trait_impl
                 },
                 quote! {
                     impl #name {
@@ -380,6 +383,9 @@ fn generate_syntax_kinds(grammar: KindsSrc) -> String {
         if "{}[]()".contains(token) {
             let c = token.chars().next().unwrap();
             quote! { #c }
+        } else if token.contains(|c| "{}[]()".contains(c)) {
+            // An unbalanced delimiter can't be a macro argument, as in `{|`.
+            quote! { #token }
         } else if *token == "_" {
             quote! { _ }
         } else {
@@ -392,6 +398,7 @@ fn generate_syntax_kinds(grammar: KindsSrc) -> String {
         .iter()
         .map(|(_token, name)| format_ident!("{}", name))
         .collect::<Vec<_>>();
+    let punctuation_texts = grammar.punct.iter().map(|(token, _name)| *token);
 
     let x = |&name| format_ident!("{}_KW", to_upper_snake_case(name));
     let full_keywords_values = grammar.keywords;
@@ -473,6 +480,15 @@ fn generate_syntax_kinds(grammar: KindsSrc) -> String {
                 };
                 Some(tok)
             }
+
+            pub fn text(self) -> Option<&'static str> {
+                let text = match self {
+                    #(#punctuation => #punctuation_texts,)*
+                    #(#all_keywords => #all_keywords_values,)*
+                    _ => return None,
+                };
+                Some(text)
+            }
         }
 
         #[macro_export]
@@ -558,6 +574,8 @@ impl Field {
                     "->" => "arrow",
                     "'{'" => "l_curly",
                     "'}'" => "r_curly",
+                    "\"{|\"" => "l_curly_pipe",
+                    "\"|}\"" => "pipe_r_curly",
                     "'('" => "l_paren",
                     "')'" => "r_paren",
                     "'['" => "l_brack",
@@ -707,6 +725,8 @@ fn lower_rule(acc: &mut Vec<Field>, grammar: &Grammar, label: Option<&String>, r
             let mut name = clean_token_name(&grammar[*token].name);
             if "[]{}()".contains(&name) {
                 name = format!("'{name}'");
+            } else if name.contains(|c| "[]{}()".contains(c)) {
+                name = format!("\"{name}\"");
             }
             let field = Field::Token(name);
             acc.push(field);
@@ -749,6 +769,13 @@ fn lower_rule(acc: &mut Vec<Field>, grammar: &Grammar, label: Option<&String>, r
                     | "condition"
                     | "args"
                     | "body"
+                    | "fun"
+                    | "arg"
+                    | "fst"
+                    | "snd"
+                    | "head"
+                    | "rest"
+                    | "ret"
             );
             if manually_implemented {
                 return;
@@ -868,16 +895,20 @@ fn extract_enums(ast: &mut AstSrc) {
     }
 }
 
-// TODO
+/// Trait impls of nodes that are written by hand in `syntax::ast::node_ext`,
+/// as `(node, trait)`.
+const MANUAL_IMPLS: &[(&str, &str)] = &[("LetDecl", "HasName"), ("AnyHasName", "HasName")];
+
+fn is_manual_impl(node: &str, trait_name: &str) -> bool {
+    MANUAL_IMPLS.contains(&(node, trait_name))
+}
+
 const TRAITS: &[(&str, &[&str])] = &[
-    // ("HasAttrs", &["attrs"]),
-    // ("HasName", &["name"]),
-    // ("HasVisibility", &["visibility"]),
-    // ("HasGenericParams", &["generic_param_list", "where_clause"]),
-    // ("HasTypeBounds", &["type_bound_list", "colon_token"]),
-    // ("HasModuleItem", &["items"]),
-    // ("HasLoopBody", &["label", "loop_body"]),
-    // ("HasArgList", &["arg_list"]),
+    ("HasAttrs", &["attrs"]),
+    ("HasName", &["name"]),
+    ("HasConstraintList", &["constraint_list"]),
+    ("HasTypeVars", &["type_vars"]),
+    ("HasParamList", &["param_list"]),
 ];
 
 fn extract_struct_traits(ast: &mut AstSrc) {
@@ -887,14 +918,32 @@ fn extract_struct_traits(ast: &mut AstSrc) {
         }
     }
 
-    // TODO
-    let nodes_with_doc_comments = [];
+    let nodes_with_doc_comments = [
+        "SourceFile",
+        "Module",
+        "LetDecl",
+        "Import",
+        "Class",
+        "Where",
+        "Union",
+        "Value",
+        "Alias",
+        "AttrDef",
+    ];
 
     for node in &mut ast.nodes {
         if nodes_with_doc_comments.contains(&&*node.name) {
             node.traits.push("HasDocComments".into());
         }
     }
+
+    // The name of a `let` is in its head pattern rather than a `name` field,
+    // see `MANUAL_IMPLS`.
+    for node in &mut ast.nodes {
+        if node.name == "LetDecl" {
+            node.traits.push("HasName".into());
+        }
+    }
 }
 
 fn extract_struct_trait(node: &mut AstNodeSrc, trait_name: &str, methods: &[&str]) {
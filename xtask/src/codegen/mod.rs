@@ -1,4 +1,5 @@
 mod grammar;
+mod parser_inline_tests;
 
 use std::fs;
 use std::path::Path;
@@ -13,8 +14,12 @@ pub fn run(args: CodegenArgs, _sh: &Shell) -> anyhow::Result<()> {
         CodegenMode::Grammar => {
             grammar::generate(args.check);
         },
+        CodegenMode::ParserTests => {
+            parser_inline_tests::generate(args.check);
+        },
         CodegenMode::All => {
             grammar::generate(args.check);
+            parser_inline_tests::generate(args.check);
         },
     }
 
@@ -61,11 +66,11 @@ fn add_preamble(cm: CodegenMode, mut text: String) -> String {
 #[allow(clippy::print_stderr)]
 fn ensure_file_contents(cm: CodegenMode, file: &Path, contents: &str, check: bool) -> bool {
     let contents = normalize_newlines(contents);
-    if let Ok(old_contents) = fs::read_to_string(file) {
-        if normalize_newlines(&old_contents) == contents {
-            // File is already up to date.
-            return false;
-        }
+    if let Ok(old_contents) = fs::read_to_string(file)
+        && normalize_newlines(&old_contents) == contents
+    {
+        // File is already up to date.
+        return false;
     }
 
     let display_path = file.strip_prefix(project_root()).unwrap_or(file);
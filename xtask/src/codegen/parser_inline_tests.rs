@@ -0,0 +1,214 @@
+//! Extracts the `// test name` snippets from the comments of the parser's
+//! `grammar` modules into the parser test corpus.
+//!
+//! A snippet is a block of line comments, starting with `// test name` for
+//! code that parses cleanly or `// test_err name` for code with errors:
+//!
+//! ```text
+//! // test let_decl_op
+//! // let (+) x y = x
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{cli::CodegenMode, codegen::ensure_file_contents, project_root};
+
+pub(crate) fn generate(check: bool) {
+    let grammar_dir = project_root().join("crates/parser/src/grammar");
+    let tests = tests_from_dir(&grammar_dir);
+
+    let inline_dir = project_root().join("crates/parser/test_data/parser/inline");
+    install_tests(&tests.ok, &inline_dir.join("ok"), check);
+    install_tests(&tests.err, &inline_dir.join("err"), check);
+}
+
+#[derive(Debug)]
+struct Test {
+    name: String,
+    text: String,
+    /// Where the snippet comes from, for duplicate name errors.
+    location: String,
+}
+
+#[derive(Default, Debug)]
+struct Tests {
+    ok: HashMap<String, Test>,
+    err: HashMap<String, Test>,
+}
+
+fn install_tests(tests: &HashMap<String, Test>, dir: &Path, check: bool) {
+    for test in tests.values() {
+        let path = dir.join(format!("{}.fsic", test.name));
+        ensure_file_contents(CodegenMode::ParserTests, &path, &test.text, check);
+    }
+
+    // Snippets removed from the grammar leave their files, and the expected
+    // trees next to them, behind.
+    for path in existing_tests(dir) {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        if tests.contains_key(name) {
+            continue;
+        }
+        if check {
+            panic!("{} has no `// test` snippet in the grammar", path.display());
+        }
+        fs::remove_file(&path).unwrap();
+        let _ = fs::remove_file(path.with_extension("rast"));
+    }
+}
+
+fn existing_tests(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut res: Vec<PathBuf> = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().unwrap_or_default() == "fsic")
+        .collect();
+    res.sort();
+    res
+}
+
+fn tests_from_dir(dir: &Path) -> Tests {
+    let mut res = Tests::default();
+    for path in rust_files(dir) {
+        let text = fs::read_to_string(&path).unwrap();
+        let file = path
+            .strip_prefix(project_root())
+            .unwrap()
+            .display()
+            .to_string();
+        for (line, test) in collect_tests(&text) {
+            let location = format!("{file}:{line}");
+            let tests = if test.ok { &mut res.ok } else { &mut res.err };
+            let test = Test {
+                name: test.name,
+                text: test.text,
+                location,
+            };
+            if let Some(old) = tests.get(&test.name) {
+                panic!(
+                    "duplicate test `{}` at {} and {}",
+                    test.name, old.location, test.location
+                );
+            }
+            tests.insert(test.name.clone(), test);
+        }
+    }
+    res
+}
+
+fn rust_files(dir: &Path) -> Vec<PathBuf> {
+    let mut res = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().unwrap_or_default() == "rs" {
+                res.push(path);
+            }
+        }
+    }
+    res.sort();
+    res
+}
+
+#[derive(Debug, PartialEq)]
+struct CommentTest {
+    name: String,
+    text: String,
+    ok: bool,
+}
+
+/// Finds the snippets in `text`, along with the one-based lines they start
+/// at. A snippet ends with its comment block, at a doc comment or at the
+/// header of the next snippet.
+fn collect_tests(text: &str) -> Vec<(usize, CommentTest)> {
+    let mut res = Vec::new();
+    let mut lines = text.lines().enumerate().peekable();
+    while let Some((idx, line)) = lines.next() {
+        let Some((ok, name)) = test_header(line) else {
+            continue;
+        };
+
+        let mut text = String::new();
+        while let Some((_, line)) = lines.peek() {
+            if test_header(line).is_some() {
+                break;
+            }
+            let line = line.trim_start();
+            let Some(line) = line.strip_prefix("//").filter(|_| !line.starts_with("///")) else {
+                break;
+            };
+            // The space after `//` is part of the comment syntax, the rest of
+            // the indentation belongs to the snippet.
+            text.push_str(line.strip_prefix(' ').unwrap_or(line));
+            text.push('\n');
+            lines.next();
+        }
+        assert!(!text.is_empty(), "empty test `{name}` at line {}", idx + 1);
+
+        let name = name.trim().to_owned();
+        res.push((idx + 1, CommentTest { name, text, ok }));
+    }
+    res
+}
+
+/// Whether the snippet is expected to parse cleanly, and its name.
+fn test_header(line: &str) -> Option<(bool, &str)> {
+    let header = line.trim_start().strip_prefix("// ")?;
+    if let Some(name) = header.strip_prefix("test ") {
+        Some((true, name))
+    } else {
+        header.strip_prefix("test_err ").map(|name| (false, name))
+    }
+}
+
+#[test]
+fn test() {
+    generate(true);
+}
+
+#[test]
+fn snippets() {
+    let text = "
+fn f() {}
+
+// test simple
+// let x = 1
+//     |> f
+fn g() {}
+
+    // test_err broken
+    // let = 1
+    //
+    // let y
+    // test_err next
+    // let
+    /// Docs.
+fn h() {}
+";
+    assert_eq!(collect_tests(text), vec![
+        (4, CommentTest {
+            name: "simple".to_owned(),
+            text: "let x = 1\n    |> f\n".to_owned(),
+            ok: true,
+        }),
+        (9, CommentTest {
+            name: "broken".to_owned(),
+            text: "let = 1\n\nlet y\n".to_owned(),
+            ok: false,
+        }),
+        (13, CommentTest {
+            name: "next".to_owned(),
+            text: "let\n".to_owned(),
+            ok: false,
+        }),
+    ]);
+}